@@ -0,0 +1,91 @@
+use {
+    criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion},
+    mpchash::{HashRing, HashRingBuilder, RingStorage, SkipMapStorage, SortedArrayStorage},
+};
+
+const NODES: [u64; 4] = [10, 100, 1_000, 10_000];
+
+fn ring<S: RingStorage>(storage: S, nodes: u64) -> HashRing<u64> {
+    let ring = HashRingBuilder::new().storage(storage).build();
+    (0..nodes).for_each(|node| ring.add(node));
+    ring
+}
+
+/// Ring with nodes of weights from 1 to 4.
+fn weighted_ring<S: RingStorage>(storage: S, nodes: u64) -> HashRing<u64> {
+    let ring = HashRingBuilder::new().storage(storage).build();
+    (0..nodes).for_each(|node| ring.add_weighted(node, 1 + (node % 4) as u32));
+    ring
+}
+
+fn node(c: &mut Criterion) {
+    let mut group = c.benchmark_group("node");
+    for nodes in NODES {
+        let rings = [
+            ("skip_map", ring(SkipMapStorage, nodes)),
+            ("sorted_array", ring(SortedArrayStorage, nodes)),
+            ("skip_map_weighted", weighted_ring(SkipMapStorage, nodes)),
+            (
+                "sorted_array_weighted",
+                weighted_ring(SortedArrayStorage, nodes),
+            ),
+        ];
+        for (storage, ring) in &rings {
+            let mut key = 0u64;
+            group.bench_with_input(BenchmarkId::new(*storage, nodes), ring, |b, ring| {
+                b.iter(|| {
+                    key = key.wrapping_add(1);
+                    ring.node(black_box(&key)).map(|token| *token.node())
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+fn node_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("node_batch");
+    let nodes = 10_000;
+    let ring = ring(SkipMapStorage, nodes);
+    for size in [1, 16, 256, 4096] {
+        let keys = (0..size).collect::<Vec<u64>>();
+        group.bench_with_input(BenchmarkId::new("node", size), &keys, |b, keys| {
+            b.iter(|| {
+                keys.iter()
+                    .map(|key| ring.node(black_box(key)).map(|token| *token.node()))
+                    .collect::<Vec<_>>()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), &keys, |b, keys| {
+            b.iter(|| {
+                ring.node_batch(black_box(keys))
+                    .into_iter()
+                    .map(|token| token.map(|token| *token.node()))
+                    .collect::<Vec<_>>()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+    for nodes in NODES {
+        let rings = [
+            ("skip_map", ring(SkipMapStorage, nodes)),
+            ("sorted_array", ring(SortedArrayStorage, nodes)),
+        ];
+        for (storage, ring) in &rings {
+            group.bench_with_input(BenchmarkId::new(*storage, nodes), ring, |b, ring| {
+                b.iter(|| {
+                    ring.add(black_box(nodes));
+                    ring.remove(black_box(&nodes));
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, node, node_batch, add);
+criterion_main!(benches);
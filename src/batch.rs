@@ -0,0 +1,125 @@
+use {
+    crate::{distance, storage::Owner, HashRing, Partitioner, RingNode, RingPosition, RingToken},
+    std::{collections::BTreeMap, hash::Hash},
+};
+
+impl<N: RingNode, P: Partitioner<N>> HashRing<N, P> {
+    /// Returns the primary nodes responsible for the given keys.
+    ///
+    /// Produces the same result as calling [`node()`](Self::node) for every
+    /// key. Probes of all the keys are sorted, and resolved in a single pass
+    /// over the ring: consecutive probes falling into the same interval share
+    /// a single search for their owner. So, when the batch has considerably
+    /// more probes than the ring has nodes, most of the searches are avoided,
+    /// while for small batches the cost is close to that of calling
+    /// [`node()`](Self::node) for every key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// (0..10).for_each(|node| ring.add(node));
+    ///
+    /// let keys = (0..100u64).collect::<Vec<_>>();
+    /// let nodes = ring.node_batch(&keys);
+    /// for (key, token) in keys.iter().zip(nodes) {
+    ///     assert_eq!(token, ring.node(key));
+    /// }
+    /// ```
+    pub fn node_batch<K: Hash>(&self, keys: &[K]) -> Vec<Option<RingToken<'_, N>>>
+    where
+        P: Partitioner<K>,
+    {
+        let view = self.positions.view();
+
+        // Probes of all the keys, along with the key index and the probe index
+        // (the latter breaks ties the same way as for a single key).
+        let mut probes = Vec::with_capacity(keys.len() * self.probe_count);
+        for (key_idx, key) in keys.iter().enumerate() {
+            let positions = self.partitioner.positions(key, self.probe_count);
+            probes.extend(
+                positions
+                    .enumerate()
+                    .map(|(probe_idx, pos)| (pos, key_idx, probe_idx)),
+            );
+        }
+        probes.sort_unstable_by_key(|(pos, ..)| *pos);
+
+        // Owners resolved so far, along with their positions and weights.
+        let mut owners: Vec<(Owner<'_, N>, RingPosition, u32)> = Vec::new();
+        let mut wrapped = false;
+        // Per key: the (weighted) distance of the closest probe, its index, and
+        // the index of its owner.
+        let mut closest: Vec<Option<(RingPosition, u32, usize, usize)>> = vec![None; keys.len()];
+        for (pos, key_idx, probe_idx) in probes {
+            // Probes are sorted, so the owner of the previous probe also owns
+            // this one, unless some node is located in between. Once wrapped
+            // around, the owner is the first node for the remaining probes.
+            let reuse = owners
+                .last()
+                .is_some_and(|(_, owner_pos, _)| wrapped || pos < *owner_pos);
+            if !reuse {
+                let Some(owner) = view.owner(pos) else {
+                    return keys.iter().map(|_| None).collect();
+                };
+                let owner_pos = owner.position();
+                wrapped = owner_pos <= pos;
+                let weight = view.weight(&owner);
+                owners.push((owner, owner_pos, weight));
+            }
+            let owner_idx = owners.len() - 1;
+            let (_, owner_pos, weight) = owners[owner_idx];
+            let distance = distance(pos, owner_pos);
+
+            let closer = match closest[key_idx] {
+                None => true,
+                Some((min_distance, min_weight, min_probe, _)) => {
+                    let lhs = u128::from(distance) * u128::from(min_weight);
+                    let rhs = u128::from(min_distance) * u128::from(weight);
+                    lhs < rhs || (lhs == rhs && probe_idx < min_probe)
+                }
+            };
+            if closer {
+                closest[key_idx] = Some((distance, weight, probe_idx, owner_idx));
+            }
+        }
+
+        closest
+            .into_iter()
+            .map(|closest| closest.map(|(.., owner_idx)| view.token(owners[owner_idx].0.clone())))
+            .collect()
+    }
+
+    /// Groups the given keys by the primary nodes responsible for them.
+    ///
+    /// Groups are ordered by node positions. See
+    /// [`node_batch()`](Self::node_batch) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// (0..10).for_each(|node| ring.add(node));
+    ///
+    /// let keys = (0..100u64).collect::<Vec<_>>();
+    /// for (token, keys) in ring.group_by_node(&keys) {
+    ///     assert!(keys.iter().all(|key| ring.node(key) == Some(token.clone())));
+    /// }
+    /// ```
+    pub fn group_by_node<'k, K: Hash>(&self, keys: &'k [K]) -> Vec<(RingToken<'_, N>, Vec<&'k K>)>
+    where
+        P: Partitioner<K>,
+    {
+        let mut groups = BTreeMap::new();
+        for (key, token) in keys.iter().zip(self.node_batch(keys)) {
+            if let Some(token) = token {
+                groups
+                    .entry(token.position())
+                    .or_insert_with(|| (token, Vec::new()))
+                    .1
+                    .push(key);
+            }
+        }
+        groups.into_values().collect()
+    }
+}
@@ -0,0 +1,279 @@
+//! Inspects and queries a ring described in a TOML file.
+//!
+//! Ring description lists the nodes, along with optional ring settings:
+//!
+//! ```toml
+//! probe_count = 23        # optional, defaults to `DEFAULT_PROBE_COUNT`
+//! seeds = [4242, 9001]    # optional, see `HashRingBuilder::seeds`
+//!
+//! [[nodes]]
+//! name = "cache-1"
+//!
+//! [[nodes]]
+//! name = "cache-2"
+//! weight = 2              # optional, see `HashRing::add_weighted`
+//!
+//! [[nodes]]
+//! name = "cache-3"
+//! position = 42           # optional, see `HashRing::insert`
+//! ```
+//!
+//! Setting `seeds` (to any values, including `DEFAULT_SEED1` and
+//! `DEFAULT_SEED2`) changes the position of every node and key, so the ring
+//! differs from the one built with the default partitioner.
+
+use {
+    clap::{Parser, Subcommand, ValueEnum},
+    mpchash::{HashRing, HashRingBuilder, KeyRange, RingPosition, DEFAULT_WEIGHT},
+    serde::Deserialize,
+    std::{
+        fs,
+        hash::{Hash, Hasher},
+        path::{Path, PathBuf},
+        process::ExitCode,
+    },
+};
+
+#[derive(Parser)]
+#[command(name = "mpchash", about = "Inspects and queries a hash ring")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the node owning a key.
+    Lookup {
+        #[command(flatten)]
+        ring: RingArg,
+
+        #[command(flatten)]
+        key: KeyArg,
+    },
+
+    /// Prints the nodes storing replicas of a key (primary node first).
+    Replicas {
+        #[command(flatten)]
+        ring: RingArg,
+
+        #[command(flatten)]
+        key: KeyArg,
+
+        /// Number of replicas.
+        #[arg(short, default_value_t = 3)]
+        n: usize,
+    },
+
+    /// Prints the key ranges owned by a node.
+    Intervals {
+        #[command(flatten)]
+        ring: RingArg,
+
+        /// Node name.
+        node: String,
+    },
+
+    /// Prints the key ranges owned by all the nodes.
+    Ranges {
+        #[command(flatten)]
+        ring: RingArg,
+    },
+
+    /// Prints the difference between two rings.
+    Diff {
+        /// The old ring description.
+        old: PathBuf,
+
+        /// The new ring description.
+        new: PathBuf,
+
+        /// Number of replicas per key.
+        #[arg(short, default_value_t = 1)]
+        n: usize,
+    },
+}
+
+#[derive(clap::Args)]
+struct RingArg {
+    /// Ring description file.
+    #[arg(short, long)]
+    ring: PathBuf,
+}
+
+#[derive(clap::Args)]
+struct KeyArg {
+    /// The key.
+    key: String,
+
+    /// How the key is hashed: as a string, or as an unsigned integer.
+    #[arg(short = 't', long, value_enum, default_value_t = KeyType::Str)]
+    key_type: KeyType,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum KeyType {
+    Str,
+    U64,
+}
+
+/// Key hashed the same way the application hashes it.
+enum Key {
+    Str(String),
+    U64(u64),
+}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Str(key) => key.hash(state),
+            Self::U64(key) => key.hash(state),
+        }
+    }
+}
+
+impl KeyArg {
+    fn key(&self) -> Result<Key, String> {
+        match self.key_type {
+            KeyType::Str => Ok(Key::Str(self.key.clone())),
+            KeyType::U64 => self
+                .key
+                .parse()
+                .map(Key::U64)
+                .map_err(|err| format!("invalid key `{}`: {err}", self.key)),
+        }
+    }
+}
+
+/// Ring description file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RingFile {
+    probe_count: Option<usize>,
+    seeds: Option<[u64; 2]>,
+    #[serde(default)]
+    nodes: Vec<NodeEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeEntry {
+    name: String,
+    weight: Option<u32>,
+    position: Option<RingPosition>,
+}
+
+fn load(path: &Path) -> Result<HashRing<String>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let file: RingFile =
+        toml::from_str(&contents).map_err(|err| format!("{}: {err}", path.display()))?;
+
+    let mut builder = HashRingBuilder::new();
+    if let Some([seed1, seed2]) = file.seeds {
+        builder = builder.seeds(seed1, seed2);
+    }
+    if let Some(probe_count) = file.probe_count {
+        if probe_count == 0 {
+            return Err(format!("{}: probe count must be positive", path.display()));
+        }
+        builder = builder.probe_count(probe_count);
+    }
+    let ring = builder.build();
+    for node in file.nodes {
+        let weight = node.weight.unwrap_or(DEFAULT_WEIGHT);
+        if weight == 0 {
+            return Err(format!(
+                "{}: node `{}` has zero weight",
+                path.display(),
+                node.name
+            ));
+        }
+        match node.position {
+            Some(_) if weight != DEFAULT_WEIGHT => {
+                return Err(format!(
+                    "{}: node `{}` has both position and weight",
+                    path.display(),
+                    node.name
+                ));
+            }
+            Some(pos) => ring.insert(pos, node.name),
+            None => {
+                let name = node.name.clone();
+                ring.try_add_weighted(node.name, weight)
+                    .map_err(|err| format!("{}: node `{name}`: {err}", path.display()))?;
+            }
+        }
+    }
+    Ok(ring)
+}
+
+fn format_range(range: &KeyRange<RingPosition>) -> String {
+    format!("[{}, {})", range.start, range.end)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    match args.command {
+        Command::Lookup { ring, key } => {
+            let ring = load(&ring.ring)?;
+            let key = key.key()?;
+            let token = ring.node(&key).ok_or("ring is empty")?;
+            println!("node:     {}", token.node());
+            println!("position: {}", token.position());
+            println!("key:      {}", ring.position(&key));
+            if let Some(probe) = ring.probe_position(&key) {
+                println!("probe:    {probe}");
+            }
+        }
+        Command::Replicas { ring, key, n } => {
+            let ring = load(&ring.ring)?;
+            let key = key.key()?;
+            for token in ring.replicas(&key, n) {
+                println!("{}\t{}", token.position(), token.node());
+            }
+        }
+        Command::Intervals { ring, node } => {
+            let ring = load(&ring.ring)?;
+            let intervals = ring
+                .intervals(&node)
+                .ok_or_else(|| format!("node `{node}` not found"))?;
+            for range in intervals {
+                println!("{}", format_range(&range));
+            }
+        }
+        Command::Ranges { ring } => {
+            let ring = load(&ring.ring)?;
+            for (range, token) in ring.ownership() {
+                let share = 100.0 * range.size() as f64 / RingPosition::MAX as f64;
+                println!("{}\t{share:.2}%\t{}", format_range(&range), token.node());
+            }
+        }
+        Command::Diff { old, new, n } => {
+            let diff = load(&old)?.diff(&load(&new)?, n);
+            for (pos, node) in &diff.added {
+                println!("+ {node}\t{pos}");
+            }
+            for (pos, node) in &diff.removed {
+                println!("- {node}\t{pos}");
+            }
+            for change in &diff.changes {
+                println!(
+                    "~ {}\t{} -> {}",
+                    format_range(&change.range),
+                    change.old_replicas.join(","),
+                    change.new_replicas.join(","),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
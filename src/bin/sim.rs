@@ -0,0 +1,384 @@
+//! Simulates key distribution and movement on rings of various topologies.
+//!
+//! Builds a ring, routes synthetic keys, applies a scripted sequence of node
+//! joins and leaves, and reports balance metrics and key movement per step.
+//! Other consistent hashing algorithms can be simulated for comparison.
+//!
+//! ```text
+//! mpchash-sim --nodes 100 --keys 100000 --probes 1,8,23 --step +10 --step -5
+//! mpchash-sim --algorithms ring,jump,rendezvous,maglev,anchor --step -5
+//! ```
+
+use {
+    clap::{Parser, ValueEnum},
+    mpchash::{
+        AnchorHash,
+        ConsistentHasher,
+        HashRingBuilder,
+        JumpHash,
+        MaglevTable,
+        RendezvousHash,
+    },
+    rand::{rngs::StdRng, Rng, SeedableRng},
+    std::{collections::HashMap, fmt, str::FromStr},
+};
+
+#[derive(Parser)]
+#[command(
+    name = "mpchash-sim",
+    about = "Simulates key distribution on a hash ring"
+)]
+struct Args {
+    /// Initial number of nodes.
+    #[arg(short, long, default_value_t = 100)]
+    nodes: u64,
+
+    /// Number of synthetic keys to route.
+    #[arg(short, long, default_value_t = 100_000)]
+    keys: u64,
+
+    /// Algorithms to compare (comma-separated).
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [Algorithm::Ring]
+    )]
+    algorithms: Vec<Algorithm>,
+
+    /// Probe counts to compare (comma-separated), for the `ring` algorithm.
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_values_t = [mpchash::DEFAULT_PROBE_COUNT]
+    )]
+    probes: Vec<usize>,
+
+    /// Number of replicas per key.
+    #[arg(short, long, default_value_t = 3)]
+    replicas: usize,
+
+    /// Membership change: `+N` joins N new nodes, `-N` removes N random nodes.
+    #[arg(short, long = "step", allow_hyphen_values = true)]
+    steps: Vec<Step>,
+
+    /// Seed for choosing nodes to remove.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Algorithm {
+    /// Multi-probe consistent hashing.
+    Ring,
+    /// Jump consistent hash.
+    Jump,
+    /// Rendezvous (highest random weight) hash.
+    Rendezvous,
+    /// Maglev hash.
+    Maglev,
+    /// Anchor hash.
+    Anchor,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.to_possible_value().expect("no skipped values");
+        f.write_str(name.get_name())
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+}
+
+/// Scripted membership change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Join(u64),
+    Leave(u64),
+}
+
+impl FromStr for Step {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let count = |n: &str| {
+            n.parse::<u64>()
+                .map_err(|err| format!("invalid step `{s}`: {err}"))
+        };
+        if let Some(n) = s.strip_prefix('+') {
+            Ok(Self::Join(count(n)?))
+        } else if let Some(n) = s.strip_prefix('-') {
+            Ok(Self::Leave(count(n)?))
+        } else {
+            Err(format!("invalid step `{s}`: expected `+N` or `-N`"))
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Join(n) => write!(f, "+{n}"),
+            Self::Leave(n) => write!(f, "-{n}"),
+        }
+    }
+}
+
+/// Metrics collected after a single step.
+struct Report {
+    algorithm: Algorithm,
+    probes: Option<usize>,
+    step: String,
+    nodes: usize,
+    peak_to_average: f64,
+    std_dev: f64,
+    moved: f64,
+    replicas_moved: f64,
+}
+
+const COLUMNS: [&str; 8] = [
+    "algorithm",
+    "probes",
+    "step",
+    "nodes",
+    "peak/avg",
+    "std dev",
+    "moved %",
+    "replicas moved %",
+];
+
+impl Report {
+    fn columns(&self) -> [String; 8] {
+        [
+            self.algorithm.to_string(),
+            self.probes
+                .map_or_else(|| "-".to_string(), |probes| probes.to_string()),
+            self.step.clone(),
+            self.nodes.to_string(),
+            format!("{:.3}", self.peak_to_average),
+            format!("{:.1}", self.std_dev),
+            format!("{:.2}", self.moved),
+            format!("{:.2}", self.replicas_moved),
+        ]
+    }
+}
+
+/// Owners of every key: primary node and replicas.
+type Routing = Vec<Vec<u64>>;
+
+/// Hasher along with its live nodes.
+struct Simulation {
+    algorithm: Algorithm,
+    probes: Option<usize>,
+    hasher: Box<dyn ConsistentHasher<u64, Node = u64>>,
+    live: Vec<u64>,
+    next_id: u64,
+    keys: u64,
+    replicas: usize,
+}
+
+impl Simulation {
+    fn new(args: &Args, algorithm: Algorithm, probes: Option<usize>) -> Self {
+        let mut hasher: Box<dyn ConsistentHasher<u64, Node = u64>> = match algorithm {
+            Algorithm::Ring => {
+                let probe_count = probes.unwrap_or(mpchash::DEFAULT_PROBE_COUNT);
+                Box::new(HashRingBuilder::new().probe_count(probe_count).build())
+            }
+            Algorithm::Jump => Box::new(JumpHash::new()),
+            Algorithm::Rendezvous => Box::new(RendezvousHash::new()),
+            Algorithm::Maglev => Box::new(MaglevTable::new()),
+            Algorithm::Anchor => {
+                // Anchor must fit all the nodes which ever join.
+                let joins = args.steps.iter().map(|step| match step {
+                    Step::Join(n) => *n,
+                    Step::Leave(_) => 0,
+                });
+                let capacity = args.nodes + joins.sum::<u64>();
+                Box::new(AnchorHash::new(capacity.max(1) as usize))
+            }
+        };
+        let live = (0..args.nodes).collect::<Vec<_>>();
+        live.iter().for_each(|id| hasher.add(*id));
+        Self {
+            algorithm,
+            probes,
+            hasher,
+            live,
+            next_id: args.nodes,
+            keys: args.keys,
+            replicas: args.replicas,
+        }
+    }
+
+    fn apply(&mut self, step: Step, rng: &mut StdRng) {
+        match step {
+            Step::Join(n) => {
+                for _ in 0..n {
+                    self.hasher.add(self.next_id);
+                    self.live.push(self.next_id);
+                    self.next_id += 1;
+                }
+            }
+            Step::Leave(n) => {
+                for _ in 0..n.min(self.live.len() as u64) {
+                    let node = self.live.swap_remove(rng.random_range(0..self.live.len()));
+                    self.hasher.remove(&node);
+                }
+            }
+        }
+    }
+
+    fn route(&self) -> Routing {
+        (0..self.keys)
+            .map(|key| self.hasher.replicas(&key, self.replicas))
+            .collect()
+    }
+
+    fn report(&self, step: String, before: &Routing, after: &Routing) -> Report {
+        let moved = before
+            .iter()
+            .zip(after)
+            .filter(|(old, new)| old.first() != new.first())
+            .count();
+        let replicas_moved = before
+            .iter()
+            .zip(after)
+            .map(|(old, new)| new.iter().filter(|node| !old.contains(node)).count())
+            .sum::<usize>();
+        let percent = |n: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                100.0 * n as f64 / total as f64
+            }
+        };
+        // Load of every live node (including the ones owning no keys).
+        let mut loads = self
+            .live
+            .iter()
+            .map(|node| (*node, 0))
+            .collect::<HashMap<_, _>>();
+        for owners in after {
+            if let Some(load) = owners.first().and_then(|node| loads.get_mut(node)) {
+                *load += 1;
+            }
+        }
+        let mean = if loads.is_empty() {
+            0.0
+        } else {
+            self.keys as f64 / loads.len() as f64
+        };
+        let variance = loads
+            .values()
+            .map(|load| (f64::from(*load) - mean).powi(2))
+            .sum::<f64>()
+            / loads.len().max(1) as f64;
+        let peak = loads.values().copied().max().unwrap_or(0);
+        Report {
+            algorithm: self.algorithm,
+            probes: self.probes,
+            step,
+            nodes: self.hasher.len(),
+            peak_to_average: if mean > 0.0 {
+                f64::from(peak) / mean
+            } else {
+                0.0
+            },
+            std_dev: variance.sqrt(),
+            moved: percent(moved, before.len()),
+            replicas_moved: percent(replicas_moved, after.iter().map(Vec::len).sum()),
+        }
+    }
+}
+
+fn simulate(args: &Args) -> Vec<Report> {
+    let mut reports = Vec::new();
+    for &algorithm in &args.algorithms {
+        // Probe count only affects the ring.
+        let probes = if algorithm == Algorithm::Ring {
+            args.probes.iter().copied().map(Some).collect()
+        } else {
+            vec![None]
+        };
+        for probes in probes {
+            let mut rng = StdRng::seed_from_u64(args.seed);
+            let mut sim = Simulation::new(args, algorithm, probes);
+            let mut routing = sim.route();
+            reports.push(sim.report("init".to_string(), &routing, &routing));
+            for step in &args.steps {
+                sim.apply(*step, &mut rng);
+                let next = sim.route();
+                reports.push(sim.report(step.to_string(), &routing, &next));
+                routing = next;
+            }
+        }
+    }
+    reports
+}
+
+fn print_table(reports: &[Report]) {
+    let rows = reports.iter().map(Report::columns).collect::<Vec<_>>();
+    let widths = COLUMNS.map(str::len);
+    let widths = rows.iter().fold(widths, |mut widths, row| {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+        widths
+    });
+    let line = |cells: &[&str]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    println!("{}", line(&COLUMNS));
+    for row in &rows {
+        println!("{}", line(&row.each_ref().map(String::as_str)));
+    }
+}
+
+fn print_csv(reports: &[Report]) {
+    println!("{}", COLUMNS.join(","));
+    for report in reports {
+        println!("{}", report.columns().join(","));
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    if args.probes.contains(&0) {
+        eprintln!("error: probe count must be positive");
+        std::process::exit(2);
+    }
+    let reports = simulate(&args);
+    match args.format {
+        Format::Table => print_table(&reports),
+        Format::Csv => print_csv(&reports),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_steps() {
+        assert_eq!("+5".parse(), Ok(Step::Join(5)));
+        assert_eq!("-10".parse(), Ok(Step::Leave(10)));
+        assert!("5".parse::<Step>().is_err());
+        assert!("+x".parse::<Step>().is_err());
+        assert_eq!(Step::Leave(3).to_string(), "-3");
+    }
+}
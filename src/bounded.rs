@@ -0,0 +1,266 @@
+use {
+    crate::{
+        DefaultPartitioner,
+        HashRing,
+        Partitioner,
+        RingDirection::Clockwise,
+        RingEvent,
+        RingNode,
+        RingPosition,
+        RingToken,
+    },
+    std::{
+        collections::HashMap,
+        hash::Hash,
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+    },
+};
+
+/// Consistent hash ring with bounded loads.
+///
+/// Implements [Consistent Hashing with Bounded Loads](https://arxiv.org/abs/1608.01350)
+/// on top of [`HashRing`]: every assigned key increases the load of its node,
+/// and no node is allowed to have load above `ceil(c * m * w / W)`, where `c`
+/// is the capacity factor, `m` is the number of assigned keys (including the
+/// one being assigned), `w` is the weight of the node, and `W` is the total
+/// weight of the nodes on the ring (so, with equal weights, the bound is
+/// `ceil(c * m / n)` for a ring of `n` nodes).
+///
+/// Whenever the node selected by the multi-probe lookup is at capacity, the
+/// ring is traversed clockwise, and the first node with spare capacity is
+/// used instead.
+///
+/// The underlying ring can be modified concurrently (use a clone of the ring
+/// passed on construction), loads are tracked per node position. Once a node
+/// is removed from the ring, its load is dropped: the keys assigned to it no
+/// longer count towards the total load, and need not be released.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{BoundedLoadRing, HashRing};
+///
+/// let ring = HashRing::new();
+/// ring.add(1u64);
+/// ring.add(2u64);
+///
+/// let bounded = BoundedLoadRing::new(ring, 1.25);
+/// let token = bounded.assign(&"key").expect("empty ring");
+/// assert_eq!(bounded.load(&token), 1);
+/// assert_eq!(bounded.total_load(), 1);
+///
+/// assert!(bounded.release(&"key"));
+/// assert_eq!(bounded.load(&token), 0);
+/// ```
+pub struct BoundedLoadRing<N: RingNode, P = DefaultPartitioner> {
+    /// The underlying hash ring.
+    ring: HashRing<N, P>,
+
+    /// Maximum allowed ratio of node load to the average load.
+    capacity_factor: f64,
+
+    /// Current loads and assignments.
+    ///
+    /// Shared with the ring subscriber, dropping loads of removed nodes.
+    state: Arc<Mutex<LoadState>>,
+}
+
+/// Load tracking state of the bounded ring.
+#[derive(Default)]
+struct LoadState {
+    /// Number of keys assigned to a node, keyed by node position.
+    loads: HashMap<RingPosition, usize>,
+
+    /// Positions of nodes a key is currently assigned to, keyed by key
+    /// position.
+    ///
+    /// The same key can be assigned several times (and to different nodes).
+    assignments: HashMap<RingPosition, Vec<RingPosition>>,
+
+    /// Total number of assigned keys.
+    total: usize,
+}
+
+impl<N: RingNode, P: Partitioner<N>> BoundedLoadRing<N, P> {
+    /// Creates a bounded-load ring on top of the given ring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity_factor` is less than `1.0`.
+    pub fn new(ring: HashRing<N, P>, capacity_factor: f64) -> Self {
+        assert!(
+            capacity_factor >= 1.0,
+            "capacity factor must be at least 1.0"
+        );
+        let state = Arc::new(Mutex::new(LoadState::default()));
+        let subscriber = Arc::downgrade(&state);
+        ring.register(Arc::new(move |event| {
+            let Some(state) = subscriber.upgrade() else {
+                return false;
+            };
+            if let RingEvent::NodeRemoved { position, .. } = event {
+                state
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .drop_position(*position);
+            }
+            true
+        }));
+        Self {
+            ring,
+            capacity_factor,
+            state,
+        }
+    }
+
+    /// Returns the underlying hash ring.
+    pub fn ring(&self) -> &HashRing<N, P> {
+        &self.ring
+    }
+
+    /// Returns the capacity factor.
+    pub fn capacity_factor(&self) -> f64 {
+        self.capacity_factor
+    }
+
+    /// Returns the maximum load the given node may have after the next
+    /// assignment.
+    ///
+    /// Capacity is proportional to the weight of the node. For a node placed
+    /// at several positions (see [`HashRing::insert`]), capacities of all the
+    /// positions are summed up. Returns `0` if the node is not part of the
+    /// ring.
+    pub fn capacity(&self, node: &N) -> usize {
+        let capacity = self.capacity_for(self.state().total + 1);
+        self.ring
+            .nodes()
+            .filter(|token| token.node() == node)
+            .map(|token| capacity(token.position()))
+            .sum()
+    }
+
+    /// Assigns the key to a node, increasing its load.
+    ///
+    /// The node returned by [`HashRing::node`] is used if it has spare
+    /// capacity, otherwise the first node (moving clockwise) with spare
+    /// capacity is selected.
+    ///
+    /// Whenever the ring is empty, `None` is returned.
+    pub fn assign<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        let primary = self.ring.node(key)?;
+        let mut state = self.state();
+        let capacity = self.capacity_for(state.total + 1);
+
+        // Since capacities of all nodes sum up to at least `total + 1`, there's
+        // always a node with spare capacity.
+        let token = self
+            .ring
+            .tokens(primary.position(), Clockwise)
+            .find(|token| state.load(token.position()) < capacity(token.position()))
+            .unwrap_or(primary);
+
+        *state.loads.entry(token.position()).or_default() += 1;
+        state
+            .assignments
+            .entry(self.ring.position(key))
+            .or_default()
+            .push(token.position());
+        state.total += 1;
+
+        Some(token)
+    }
+
+    /// Releases a previous assignment of the key, decreasing its node load.
+    ///
+    /// Returns `false` if the key has no active assignments (assignments to
+    /// the nodes removed from the ring are dropped along with their loads).
+    pub fn release<K: Hash>(&self, key: &K) -> bool
+    where
+        P: Partitioner<K>,
+    {
+        let key_pos = self.ring.position(key);
+        let mut state = self.state();
+        let Some(positions) = state.assignments.get_mut(&key_pos) else {
+            return false;
+        };
+        let pos = positions.pop().expect("no empty assignments are kept");
+        if positions.is_empty() {
+            state.assignments.remove(&key_pos);
+        }
+
+        if let Some(load) = state.loads.get_mut(&pos) {
+            *load -= 1;
+            if *load == 0 {
+                state.loads.remove(&pos);
+            }
+        }
+        state.total -= 1;
+
+        true
+    }
+
+    /// Returns the number of keys currently assigned to the given node.
+    ///
+    /// Loads of all the positions of the node are summed up (see
+    /// [`HashRing::insert`]).
+    pub fn load(&self, node: &N) -> usize {
+        let state = self.state();
+        self.ring
+            .nodes()
+            .filter(|token| token.node() == node)
+            .map(|token| state.load(token.position()))
+            .sum()
+    }
+
+    /// Returns current loads of all the nodes on the ring.
+    pub fn loads(&self) -> Vec<(RingToken<'_, N>, usize)> {
+        let state = self.state();
+        self.ring
+            .tokens(0, Clockwise)
+            .map(|token| {
+                let load = state.load(token.position());
+                (token, load)
+            })
+            .collect()
+    }
+
+    /// Returns the total number of assigned keys.
+    pub fn total_load(&self) -> usize {
+        self.state().total
+    }
+
+    /// Returns capacities of the node positions, given the total number of
+    /// assigned keys.
+    fn capacity_for(&self, total: usize) -> impl Fn(RingPosition) -> usize + '_ {
+        let total_weight = self.ring.total_weight().max(1) as f64;
+        move |pos| {
+            let weight = f64::from(self.ring.weight_at(pos));
+            (self.capacity_factor * total as f64 * weight / total_weight).ceil() as usize
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, LoadState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl LoadState {
+    fn load(&self, pos: RingPosition) -> usize {
+        self.loads.get(&pos).copied().unwrap_or_default()
+    }
+
+    /// Drops the load of a removed node, along with its assignments.
+    fn drop_position(&mut self, pos: RingPosition) {
+        let Some(load) = self.loads.remove(&pos) else {
+            return;
+        };
+        self.total -= load;
+        self.assignments.retain(|_, positions| {
+            positions.retain(|p| *p != pos);
+            !positions.is_empty()
+        });
+    }
+}
@@ -0,0 +1,135 @@
+use {
+    crate::{
+        DefaultPartitioner,
+        HashRing,
+        RingNode,
+        RingPosition,
+        RingStorage,
+        SkipMapStorage,
+        Xxh3Partitioner,
+    },
+    crossbeam_skiplist::SkipMap,
+    std::sync::{atomic::AtomicU64, Arc, RwLock},
+};
+
+/// Builder for [`HashRing`].
+///
+/// Allows to configure the number of probes per key, the keyspace
+/// partitioner and the storage backend, falling back to
+/// [`DEFAULT_PROBE_COUNT`], [`DefaultPartitioner`] and [`SkipMapStorage`]
+/// respectively.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{HashRingBuilder, Xxh3Partitioner};
+///
+/// let ring = HashRingBuilder::new()
+///     .probe_count(11)
+///     .partitioner(Xxh3Partitioner::with_seeds(1, 2))
+///     .build::<u64>();
+/// ring.add(1);
+/// assert_eq!(ring.probe_count(), 11);
+/// ```
+///
+/// [`DEFAULT_PROBE_COUNT`]: crate::DEFAULT_PROBE_COUNT
+#[derive(Clone)]
+pub struct HashRingBuilder<P = DefaultPartitioner, S = SkipMapStorage> {
+    /// Partitioner used to compute ring positions.
+    partitioner: P,
+
+    /// The number of positions to probe for a given key.
+    probe_count: usize,
+
+    /// Storage backend of node positions.
+    storage: S,
+}
+
+impl Default for HashRingBuilder {
+    fn default() -> Self {
+        Self {
+            partitioner: DefaultPartitioner::new(),
+            probe_count: crate::DEFAULT_PROBE_COUNT,
+            storage: SkipMapStorage,
+        }
+    }
+}
+
+impl HashRingBuilder {
+    /// Creates a new builder with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> HashRingBuilder<Xxh3Partitioner, S> {
+    /// Sets seeds of the XXH3 partitioner.
+    ///
+    /// Note that even [`DEFAULT_SEED1`] and [`DEFAULT_SEED2`] change placement
+    /// of all the nodes and keys, compared to a ring built with the default
+    /// partitioner. See [`Xxh3Partitioner::with_seeds`] for details, and
+    /// [`Xxh3Partitioner::with_probe_seeds`] for the seeds of the default
+    /// partitioner.
+    ///
+    /// [`DEFAULT_SEED1`]: crate::DEFAULT_SEED1
+    /// [`DEFAULT_SEED2`]: crate::DEFAULT_SEED2
+    pub fn seeds(self, seed1: RingPosition, seed2: RingPosition) -> Self {
+        self.partitioner(Xxh3Partitioner::with_seeds(seed1, seed2))
+    }
+}
+
+impl<P, S> HashRingBuilder<P, S> {
+    /// Sets the number of positions probed for a given key.
+    ///
+    /// More probes result in a better balanced ring, at the expense of slower
+    /// lookups: for `probe_count > 1`, the peak-to-average load ratio is
+    /// `1 + 1/(probe_count - 1)`. With a single probe, the ring degenerates
+    /// into the classic consistent hashing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `probe_count` is zero.
+    pub fn probe_count(self, probe_count: usize) -> Self {
+        assert!(probe_count > 0, "probe count must be positive");
+        Self {
+            probe_count,
+            ..self
+        }
+    }
+
+    /// Sets the partitioner used to compute ring positions.
+    pub fn partitioner<Q>(self, partitioner: Q) -> HashRingBuilder<Q, S> {
+        HashRingBuilder {
+            partitioner,
+            probe_count: self.probe_count,
+            storage: self.storage,
+        }
+    }
+
+    /// Sets the storage backend of node positions.
+    ///
+    /// See [`RingStorage`] for the available backends.
+    pub fn storage<T: RingStorage>(self, storage: T) -> HashRingBuilder<P, T> {
+        HashRingBuilder {
+            partitioner: self.partitioner,
+            probe_count: self.probe_count,
+            storage,
+        }
+    }
+
+    /// Creates a new (empty) hash ring.
+    pub fn build<N: RingNode>(self) -> HashRing<N, P>
+    where
+        S: RingStorage,
+    {
+        HashRing {
+            partitioner: self.partitioner,
+            positions: Arc::new(self.storage.positions()),
+            health: Arc::new(SkipMap::new()),
+            probe_count: self.probe_count,
+            epoch: Arc::new(AtomicU64::new(0)),
+            mutations: Arc::new(RwLock::new(())),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use {
+    crate::{HashRing, KeyRange, Partitioner, RingDirection::Clockwise, RingNode, RingPosition},
+    std::{
+        collections::{BTreeSet, HashMap},
+        hash::{BuildHasher, RandomState},
+    },
+};
+
+/// Difference between two states of a ring.
+///
+/// See [`HashRing::diff`] for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingDiff<N> {
+    /// Tokens present in the new ring only (ordered by position).
+    pub added: Vec<(RingPosition, N)>,
+
+    /// Tokens present in the old ring only (ordered by position).
+    pub removed: Vec<(RingPosition, N)>,
+
+    /// Changes of owners of keys (ordered by region position). See
+    /// [`RegionChange`] for details.
+    pub changes: Vec<RegionChange<N>>,
+}
+
+/// Change of owners of keys within a region of the key space.
+///
+/// As with [`HashRing::intervals`], the region is expressed in terms of probe
+/// positions on the new ring: keys whose winning probe on the new ring falls
+/// into the region, and which are stored on `old_replicas` in the old ring,
+/// are stored on `new_replicas` in the new ring.
+///
+/// Under multi-probe hashing, keys captured by a region may come from several
+/// old owners, so there may be several changes for the same region (one per
+/// old replica set).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionChange<N> {
+    /// The region of the key space (probe positions on the new ring).
+    pub range: KeyRange<RingPosition>,
+
+    /// Nodes storing the keys in the old ring, primary owner first.
+    pub old_replicas: Vec<N>,
+
+    /// Nodes storing the keys in the new ring, primary owner first.
+    pub new_replicas: Vec<N>,
+}
+
+impl<N> RingDiff<N> {
+    /// Returns `true` if rings are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changes.is_empty()
+    }
+}
+
+impl<N> RegionChange<N> {
+    /// Returns the primary owner of the region in the old ring.
+    pub fn old_primary(&self) -> Option<&N> {
+        self.old_replicas.first()
+    }
+
+    /// Returns the primary owner of the region in the new ring.
+    pub fn new_primary(&self) -> Option<&N> {
+        self.new_replicas.first()
+    }
+}
+
+impl<N, P> HashRing<N, P>
+where
+    N: RingNode + Clone,
+    P: Partitioner<N>,
+{
+    /// Returns the difference between this (old) ring and the `other` (new)
+    /// one.
+    ///
+    /// Each key is expected to be stored on `replication_factor` nodes (see
+    /// [`replicas()`](Self::replicas)). Keys, for which either the primary
+    /// owner or any of the replicas differ, are reported as region changes.
+    ///
+    /// Under multi-probe hashing, a token added to the ring captures keys from
+    /// all the nodes (not only from its clockwise neighbour), and node weights
+    /// affect which probe wins for a given key. So, for every region of the
+    /// new ring, all the old owners which may have lost keys to it are listed.
+    /// Listed changes may match no keys, but every moved key matches exactly
+    /// one change. Both rings are expected to use the same partitioner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let old = mpchash::HashRing::<u64>::new();
+    /// old.insert(10, 1);
+    /// old.insert(20, 2);
+    ///
+    /// let new = mpchash::HashRing::<u64>::new();
+    /// new.insert(10, 1);
+    /// new.insert(20, 2);
+    /// new.insert(15, 3);
+    ///
+    /// let diff = old.diff(&new, 1);
+    /// assert_eq!(diff.added, vec![(15, 3)]);
+    /// assert!(diff.removed.is_empty());
+    ///
+    /// // Region `[10, 15)` is now owned by the new node, which captures keys
+    /// // of both the old nodes.
+    /// assert_eq!(diff.changes.len(), 2);
+    /// for change in &diff.changes {
+    ///     assert_eq!(change.range, mpchash::KeyRange::new(10, 15));
+    ///     assert_eq!(change.new_primary(), Some(&3));
+    /// }
+    /// assert_eq!(diff.changes[0].old_primary(), Some(&1));
+    /// assert_eq!(diff.changes[1].old_primary(), Some(&2));
+    /// ```
+    pub fn diff<Q>(&self, other: &HashRing<N, Q>, replication_factor: usize) -> RingDiff<N>
+    where
+        Q: Partitioner<N>,
+    {
+        let old = self.weighted_tokens();
+        let new = other.weighted_tokens();
+        let same = |a: &WeightedToken<N>, b: &WeightedToken<N>| a.1 == b.1 && a.2 == b.2;
+        let old_only = difference(&old, &new, same);
+        let new_only = difference(&new, &old, same);
+
+        // Old tokens which may lose keys to any node: removed (or re-weighted)
+        // ones, and the ones preceded by a lighter added token (some of their
+        // keys are now better off elsewhere).
+        let mut losing = old_only
+            .iter()
+            .map(|(pos, ..)| *pos)
+            .collect::<BTreeSet<_>>();
+        for (pos, _, weight) in &new_only {
+            if let Some(owner) = self.owner(pos.wrapping_sub(1)) {
+                if self.weight_at(owner.position()) > *weight {
+                    losing.insert(owner.position());
+                }
+            }
+        }
+
+        // New tokens which may capture keys from any node: added (or
+        // re-weighted) ones, and the ones heavier than a removed token they
+        // succeed. With a different probe count, any key may move anywhere.
+        let mut capturing = new_only
+            .iter()
+            .map(|(pos, ..)| *pos)
+            .collect::<BTreeSet<_>>();
+        for (pos, _, weight) in &old_only {
+            if let Some(owner) = other.owner(pos.wrapping_sub(1)) {
+                if other.weight_at(owner.position()) > *weight {
+                    capturing.insert(owner.position());
+                }
+            }
+        }
+        let rewired = self.probe_count != other.probe_count;
+
+        let hasher = RandomState::new();
+        let mut changes = Vec::new();
+        for (range, owner) in other.ownership() {
+            let new_replicas = other.replicas_at(owner.position(), replication_factor);
+            let sources = if rewired || capturing.contains(&owner.position()) {
+                old.iter().map(|(pos, ..)| *pos).collect::<Vec<_>>()
+            } else {
+                // The owner is on the old ring too (at the same position).
+                let mut sources = losing.iter().copied().collect::<Vec<_>>();
+                if !losing.contains(&owner.position()) {
+                    sources.push(owner.position());
+                }
+                sources
+            };
+
+            // Several old tokens may share the same replica set (e.g. tokens of
+            // the same node, when there are no replicas).
+            let mut listed = HashMap::<u64, Vec<Vec<N>>>::new();
+            for pos in sources {
+                let old_replicas = self.replicas_at(pos, replication_factor);
+                if old_replicas == new_replicas {
+                    continue;
+                }
+                let bucket = listed.entry(hasher.hash_one(&old_replicas)).or_default();
+                if bucket.contains(&old_replicas) {
+                    continue;
+                }
+                bucket.push(old_replicas.clone());
+                changes.push(RegionChange {
+                    range: range.clone(),
+                    old_replicas,
+                    new_replicas: new_replicas.clone(),
+                });
+            }
+        }
+
+        // Re-weighted tokens are neither added nor removed.
+        let added = difference(&new_only, &old, |a, b| a.1 == b.1);
+        let removed = difference(&old_only, &new, |a, b| a.1 == b.1);
+        RingDiff {
+            added: added
+                .into_iter()
+                .map(|(pos, node, _)| (pos, node))
+                .collect(),
+            removed: removed
+                .into_iter()
+                .map(|(pos, node, _)| (pos, node))
+                .collect(),
+            changes,
+        }
+    }
+
+    /// Returns (cloned) tokens of the ring, together with their weights.
+    fn weighted_tokens(&self) -> Vec<WeightedToken<N>> {
+        self.tokens(0, Clockwise)
+            .map(|token| {
+                let pos = token.position();
+                (pos, token.node().clone(), self.weight_at(pos))
+            })
+            .collect()
+    }
+
+    /// Returns (cloned) nodes owning the region ending at the given position.
+    fn replicas_at(&self, end: RingPosition, k: usize) -> Vec<N> {
+        self.tokens(end, Clockwise)
+            .take(k)
+            .map(|token| token.node().clone())
+            .collect()
+    }
+}
+
+/// Token of a ring, together with the weight of its node.
+type WeightedToken<N> = (RingPosition, N, u32);
+
+/// Returns tokens from `a` which have no `same` token at the same position in
+/// `b` (both ordered by position).
+fn difference<N, F>(
+    a: &[WeightedToken<N>],
+    b: &[WeightedToken<N>],
+    same: F,
+) -> Vec<WeightedToken<N>>
+where
+    N: Clone,
+    F: Fn(&WeightedToken<N>, &WeightedToken<N>) -> bool,
+{
+    a.iter()
+        .filter(|token| {
+            b.binary_search_by_key(&token.0, |(pos, ..)| *pos)
+                .map_or(true, |idx| !same(&b[idx], token))
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,119 @@
+use {
+    crate::RingPosition,
+    std::{error::Error, fmt},
+};
+
+/// Error returned when a node cannot be placed on the ring, as all its
+/// candidate positions are taken by other nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionError {
+    /// The (first candidate) position of the node.
+    pub position: RingPosition,
+}
+
+impl fmt::Display for CollisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no free ring position for node at position {}",
+            self.position
+        )
+    }
+}
+
+impl Error for CollisionError {}
+
+/// Error returned when a ring cannot be restored from its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The number of probes is zero.
+    ZeroProbeCount,
+
+    /// Node at the given position has zero weight.
+    ZeroWeight(RingPosition),
+
+    /// Several nodes share the same position.
+    DuplicatePosition(RingPosition),
+
+    /// Node position doesn't match the one computed by the partitioner.
+    PositionMismatch(RingPosition),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroProbeCount => write!(f, "probe count must be positive"),
+            Self::ZeroWeight(pos) => write!(f, "node at position {pos} has zero weight"),
+            Self::DuplicatePosition(pos) => write!(f, "several nodes at position {pos}"),
+            Self::PositionMismatch(pos) => {
+                write!(f, "node at position {pos} doesn't match partitioner")
+            }
+        }
+    }
+}
+
+impl Error for StateError {}
+
+/// Error returned when a ring cannot be decoded from its wire format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireError {
+    /// Bytes don't start with the expected magic bytes.
+    InvalidMagic,
+
+    /// Bytes end prematurely.
+    UnexpectedEof,
+
+    /// Checksum doesn't match the contents.
+    ChecksumMismatch,
+
+    /// Format version is not supported.
+    UnsupportedVersion(u8),
+
+    /// Reserved flags are set.
+    UnsupportedFlags(u8),
+
+    /// Ring was encoded with a different partitioner (given by its id).
+    PartitionerMismatch(u8),
+
+    /// Partitioner parameters are invalid.
+    InvalidPartitioner,
+
+    /// Integer is malformed or out of range.
+    InvalidVarint,
+
+    /// Node cannot be decoded.
+    InvalidNode,
+
+    /// Extra bytes follow the encoded ring.
+    TrailingBytes,
+
+    /// Decoded ring state is invalid.
+    State(StateError),
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "invalid magic bytes"),
+            Self::UnexpectedEof => write!(f, "unexpected end of input"),
+            Self::ChecksumMismatch => write!(f, "checksum mismatch"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            Self::UnsupportedFlags(flags) => write!(f, "unsupported flags {flags:#04x}"),
+            Self::PartitionerMismatch(id) => write!(f, "unexpected partitioner id {id}"),
+            Self::InvalidPartitioner => write!(f, "invalid partitioner parameters"),
+            Self::InvalidVarint => write!(f, "invalid integer"),
+            Self::InvalidNode => write!(f, "invalid node"),
+            Self::TrailingBytes => write!(f, "trailing bytes"),
+            Self::State(err) => write!(f, "invalid ring state: {err}"),
+        }
+    }
+}
+
+impl Error for WireError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::State(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use {
+    crate::{HashRing, KeyRange, Partitioner, RingNode, RingPosition},
+    std::sync::{mpsc, Arc, PoisonError},
+};
+
+/// Callback invoked on ring membership changes.
+///
+/// Returns `false` once the subscriber is no longer interested in events.
+pub(crate) type Subscriber<N> = Arc<dyn Fn(&RingEvent<&N>) -> bool + Send + Sync>;
+
+/// Membership change of a ring.
+///
+/// Range is the interval of the key space owned by the node (see
+/// [`HashRing::key_range`]): after the node is added, or before it is removed.
+/// As with [`HashRing::intervals`], the range is expressed in terms of probe
+/// positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RingEvent<N> {
+    /// Node is added to the ring.
+    NodeAdded {
+        node: N,
+        position: RingPosition,
+        range: KeyRange<RingPosition>,
+        epoch: u64,
+    },
+
+    /// Node is removed from the ring.
+    NodeRemoved {
+        node: N,
+        position: RingPosition,
+        range: KeyRange<RingPosition>,
+        epoch: u64,
+    },
+}
+
+impl<N> RingEvent<N> {
+    /// Returns the added or removed node.
+    pub fn node(&self) -> &N {
+        match self {
+            Self::NodeAdded { node, .. } | Self::NodeRemoved { node, .. } => node,
+        }
+    }
+
+    /// Returns the position of the node.
+    pub fn position(&self) -> RingPosition {
+        match self {
+            Self::NodeAdded { position, .. } | Self::NodeRemoved { position, .. } => *position,
+        }
+    }
+
+    /// Returns the key range owned by the node.
+    pub fn range(&self) -> &KeyRange<RingPosition> {
+        match self {
+            Self::NodeAdded { range, .. } | Self::NodeRemoved { range, .. } => range,
+        }
+    }
+
+    /// Returns the epoch of the ring right after the change.
+    pub fn epoch(&self) -> u64 {
+        match self {
+            Self::NodeAdded { epoch, .. } | Self::NodeRemoved { epoch, .. } => *epoch,
+        }
+    }
+}
+
+impl<N: Clone> RingEvent<&N> {
+    /// Returns the event with the node cloned.
+    pub fn cloned(&self) -> RingEvent<N> {
+        match *self {
+            Self::NodeAdded {
+                node,
+                position,
+                ref range,
+                epoch,
+            } => RingEvent::NodeAdded {
+                node: node.clone(),
+                position,
+                range: range.clone(),
+                epoch,
+            },
+            Self::NodeRemoved {
+                node,
+                position,
+                ref range,
+                epoch,
+            } => RingEvent::NodeRemoved {
+                node: node.clone(),
+                position,
+                range: range.clone(),
+                epoch,
+            },
+        }
+    }
+}
+
+impl<N: RingNode, P: Partitioner<N>> HashRing<N, P> {
+    /// Registers a callback invoked on every membership change.
+    ///
+    /// Callback is invoked synchronously, from the thread modifying the ring,
+    /// after the modification is applied. Callbacks are shared by all the
+    /// clones of the ring (but not by snapshots).
+    ///
+    /// Changing the weight of a node doesn't emit events, as the key range of
+    /// the node stays the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// let changes = Arc::new(AtomicUsize::new(0));
+    /// let counter = changes.clone();
+    /// ring.on_change(move |_| {
+    ///     counter.fetch_add(1, Ordering::SeqCst);
+    /// });
+    ///
+    /// ring.add(1);
+    /// ring.add(2);
+    /// ring.remove(&1);
+    /// assert_eq!(changes.load(Ordering::SeqCst), 3);
+    /// ```
+    pub fn on_change<F>(&self, callback: F)
+    where
+        F: Fn(&RingEvent<&N>) + Send + Sync + 'static,
+    {
+        self.register(Arc::new(move |event| {
+            callback(event);
+            true
+        }));
+    }
+
+    /// Returns a channel receiving membership changes.
+    ///
+    /// Once the receiver is dropped, the subscription is cancelled. See
+    /// [`on_change()`](Self::on_change) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, RingEvent};
+    ///
+    /// let ring = HashRing::<u64>::new();
+    /// let events = ring.subscribe();
+    ///
+    /// ring.add(1);
+    /// let event = events.recv().expect("event");
+    /// assert!(matches!(event, RingEvent::NodeAdded { node: 1, .. }));
+    /// assert_eq!(event.epoch(), ring.epoch());
+    /// ```
+    pub fn subscribe(&self) -> mpsc::Receiver<RingEvent<N>>
+    where
+        N: Clone,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.register(Arc::new(move |event| tx.send(event.cloned()).is_ok()));
+        rx
+    }
+
+    pub(crate) fn register(&self, subscriber: Subscriber<N>) {
+        self.subscribers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(subscriber);
+    }
+
+    /// Returns `true` if there are any subscribers.
+    pub(crate) fn has_subscribers(&self) -> bool {
+        !self
+            .subscribers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    /// Notifies subscribers, dropping the ones no longer interested.
+    ///
+    /// No lock is held while callbacks run, so callbacks may modify the ring.
+    pub(crate) fn notify(&self, event: &RingEvent<&N>) {
+        let subscribers = self
+            .subscribers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let done = subscribers
+            .into_iter()
+            .filter(|subscriber| !subscriber(event))
+            .collect::<Vec<_>>();
+        if !done.is_empty() {
+            self.subscribers
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .retain(|subscriber| !done.iter().any(|d| Arc::ptr_eq(subscriber, d)));
+        }
+    }
+}
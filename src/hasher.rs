@@ -0,0 +1,121 @@
+mod anchor;
+mod jump;
+mod maglev;
+mod rendezvous;
+
+use {
+    crate::{HashRing, Partitioner, RingNode},
+    std::hash::Hash,
+};
+pub use {
+    anchor::AnchorHash,
+    jump::JumpHash,
+    maglev::{MaglevTable, DEFAULT_TABLE_SIZE},
+    rendezvous::RendezvousHash,
+};
+
+/// A consistent hashing algorithm.
+///
+/// Common interface of [`HashRing`] and alternative algorithms
+/// ([`JumpHash`], [`RendezvousHash`], [`MaglevTable`] and [`AnchorHash`]),
+/// so that the algorithm can be chosen by configuration, and different
+/// algorithms can be compared using the same code.
+///
+/// The trait is object safe for a given key type. Nodes are returned by value:
+/// use the inherent methods of the implementations to avoid cloning.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{ConsistentHasher, HashRing, JumpHash, RendezvousHash};
+///
+/// fn hasher(name: &str) -> Box<dyn ConsistentHasher<&'static str, Node = u64>> {
+///     match name {
+///         "jump" => Box::new(JumpHash::new()),
+///         "rendezvous" => Box::new(RendezvousHash::new()),
+///         _ => Box::new(HashRing::new()),
+///     }
+/// }
+///
+/// for name in ["jump", "rendezvous", "ring"] {
+///     let mut hasher = hasher(name);
+///     (0..5).for_each(|node| hasher.add(node));
+///     assert_eq!(hasher.len(), 5);
+///
+///     let primary = hasher.node(&"key").unwrap();
+///     let replicas = hasher.replicas(&"key", 3);
+///     assert_eq!(replicas[0], primary);
+///
+///     hasher.remove(&primary);
+///     assert_ne!(hasher.node(&"key"), Some(primary));
+/// }
+/// ```
+pub trait ConsistentHasher<K: Hash> {
+    /// Type of the nodes keys are assigned to.
+    type Node;
+
+    /// Returns the node responsible for the given key.
+    ///
+    /// Returns `None` if there are no nodes.
+    fn node(&self, key: &K) -> Option<Self::Node>;
+
+    /// Returns up to `k` distinct nodes responsible for the given key, the
+    /// first one being the one returned by [`node()`](Self::node).
+    fn replicas(&self, key: &K, k: usize) -> Vec<Self::Node>;
+
+    /// Adds a node.
+    ///
+    /// Adding a node which is already present is a no-op.
+    ///
+    /// # Panics
+    ///
+    /// Implementations with a fixed capacity panic if there is no room for the
+    /// node, e.g. [`AnchorHash`] panics if the anchor is full (see
+    /// [`AnchorHash::capacity`]).
+    fn add(&mut self, node: Self::Node);
+
+    /// Removes a node.
+    ///
+    /// Removing a node which is not present is a no-op.
+    fn remove(&mut self, node: &Self::Node);
+
+    /// Returns the number of nodes.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no nodes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, N, P> ConsistentHasher<K> for HashRing<N, P>
+where
+    K: Hash,
+    N: RingNode + Clone,
+    P: Partitioner<N> + Partitioner<K>,
+{
+    type Node = N;
+
+    fn node(&self, key: &K) -> Option<N> {
+        HashRing::node(self, key).map(|token| token.node().clone())
+    }
+
+    fn replicas(&self, key: &K, k: usize) -> Vec<N> {
+        self.replicas_distinct(key, k)
+            .iter()
+            .map(|token| token.node().clone())
+            .collect()
+    }
+
+    fn add(&mut self, node: N) {
+        HashRing::add(self, node);
+    }
+
+    fn remove(&mut self, node: &N) {
+        HashRing::remove(self, node);
+    }
+
+    fn len(&self) -> usize {
+        HashRing::len(self)
+    }
+}
@@ -0,0 +1,212 @@
+use {
+    super::ConsistentHasher,
+    crate::{DefaultPartitioner, Partitioner, RingNode},
+    std::hash::Hash,
+};
+
+/// Anchor hash.
+///
+/// Implements the algorithm from the
+/// [AnchorHash: A Scalable Consistent Hash](https://arxiv.org/pdf/1812.09674)
+/// paper. Anchor is a fixed set of buckets (its capacity), some of which are
+/// assigned to nodes (working buckets). Keys are hashed to the anchor, and
+/// keys landing on an unassigned bucket are re-hashed to the buckets which
+/// were working at the time the bucket was removed.
+///
+/// Adding or removing a node only moves the keys of that node, and the load
+/// is perfectly balanced, at the cost of `O(capacity)` memory. Replicas of a
+/// key are the next nodes of the working set.
+#[derive(Clone)]
+pub struct AnchorHash<N, P = DefaultPartitioner> {
+    /// Partitioner used to hash keys.
+    partitioner: P,
+
+    /// Nodes, indexed by buckets (`None` for unassigned buckets).
+    nodes: Vec<Option<N>>,
+
+    /// Size of the working set at the time a bucket was removed (`A` in the
+    /// paper), zero for working buckets.
+    removed_at: Vec<usize>,
+
+    /// Bucket replacing a removed bucket (`K` in the paper).
+    replacement: Vec<usize>,
+
+    /// Working buckets, in the first `len` items (`W` in the paper).
+    working: Vec<usize>,
+
+    /// Index of a bucket in `working` (`L` in the paper).
+    location: Vec<usize>,
+
+    /// Removed buckets, the most recently removed one last (`R` in the paper).
+    removed: Vec<usize>,
+
+    /// Number of working buckets (`N` in the paper).
+    len: usize,
+}
+
+impl<N: RingNode> AnchorHash<N> {
+    /// Creates an empty anchor hash, which can hold up to `capacity` nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_partitioner(DefaultPartitioner::new(), capacity)
+    }
+}
+
+impl<N: RingNode, P> AnchorHash<N, P> {
+    /// Creates an empty anchor hash, which can hold up to `capacity` nodes,
+    /// using a given partitioner to hash keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_partitioner(partitioner: P, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        // All the buckets are removed, in the order of decreasing indexes.
+        Self {
+            partitioner,
+            nodes: (0..capacity).map(|_| None).collect(),
+            removed_at: (0..capacity).collect(),
+            replacement: (0..capacity).collect(),
+            working: (0..capacity).collect(),
+            location: (0..capacity).collect(),
+            removed: (0..capacity).rev().collect(),
+            len: 0,
+        }
+    }
+
+    /// Returns the node responsible for the given key.
+    pub fn node<K: Hash>(&self, key: &K) -> Option<&N>
+    where
+        P: Partitioner<K>,
+    {
+        self.bucket(key)
+            .and_then(|bucket| self.nodes[bucket].as_ref())
+    }
+
+    /// Returns up to `k` nodes responsible for the given key.
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<&N>
+    where
+        P: Partitioner<K>,
+    {
+        let Some(bucket) = self.bucket(key) else {
+            return Vec::new();
+        };
+        let start = self.location[bucket];
+        (0..k.min(self.len))
+            .filter_map(|i| self.nodes[self.working[(start + i) % self.len]].as_ref())
+            .collect()
+    }
+
+    /// Adds a node, assigning it the most recently removed bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the anchor is full (see [`capacity()`](Self::capacity)).
+    pub fn add(&mut self, node: N) {
+        if self.position(&node).is_some() {
+            return;
+        }
+        let bucket = self.removed.pop().expect("anchor is full");
+        self.removed_at[bucket] = 0;
+        self.location[self.working[self.len]] = self.len;
+        self.working[self.location[bucket]] = bucket;
+        self.replacement[bucket] = bucket;
+        self.len += 1;
+        self.nodes[bucket] = Some(node);
+    }
+
+    /// Removes a node.
+    pub fn remove(&mut self, node: &N) {
+        let Some(bucket) = self.position(node) else {
+            return;
+        };
+        self.nodes[bucket] = None;
+        self.removed.push(bucket);
+        self.len -= 1;
+        self.removed_at[bucket] = self.len;
+        let last = self.working[self.len];
+        self.working[self.location[bucket]] = last;
+        self.replacement[bucket] = last;
+        self.location[last] = self.location[bucket];
+    }
+
+    /// Returns the number of nodes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of nodes.
+    pub fn capacity(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the bucket assigned to a given node.
+    fn position(&self, node: &N) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|n| n.as_ref().is_some_and(|n| n == node))
+    }
+
+    /// Returns the bucket of the given key.
+    fn bucket<K: Hash>(&self, key: &K) -> Option<usize>
+    where
+        P: Partitioner<K>,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let capacity = self.capacity() as u64;
+        let mut bucket = (self.partitioner.position(key) % capacity) as usize;
+        while self.removed_at[bucket] > 0 {
+            // Re-hash into the buckets working at the time of removal.
+            let seed = bucket as u64;
+            let size = self.removed_at[bucket];
+            let mut next = (self.partitioner.position_seeded(key, seed) % size as u64) as usize;
+            while self.removed_at[next] >= size {
+                next = self.replacement[next];
+            }
+            bucket = next;
+        }
+        Some(bucket)
+    }
+}
+
+impl<K, N, P> ConsistentHasher<K> for AnchorHash<N, P>
+where
+    K: Hash,
+    N: RingNode + Clone,
+    P: Partitioner<K>,
+{
+    type Node = N;
+
+    fn node(&self, key: &K) -> Option<N> {
+        AnchorHash::node(self, key).cloned()
+    }
+
+    fn replicas(&self, key: &K, k: usize) -> Vec<N> {
+        AnchorHash::replicas(self, key, k)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn add(&mut self, node: N) {
+        AnchorHash::add(self, node);
+    }
+
+    fn remove(&mut self, node: &N) {
+        AnchorHash::remove(self, node);
+    }
+
+    fn len(&self) -> usize {
+        AnchorHash::len(self)
+    }
+}
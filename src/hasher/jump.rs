@@ -0,0 +1,175 @@
+use {
+    super::ConsistentHasher,
+    crate::{DefaultPartitioner, Partitioner, RingNode},
+    std::hash::Hash,
+};
+
+/// Jump consistent hash.
+///
+/// Implements the algorithm from the
+/// [A Fast, Minimal Memory, Consistent Hash Algorithm](https://arxiv.org/pdf/1406.2294)
+/// paper: keys are mapped to buckets `0..n` with no memory overhead, and
+/// perfect balance.
+///
+/// Nodes are numbered in the order of addition. Adding a node moves `1/n` of
+/// the keys to it. Jump hash only supports removal of the last bucket, so
+/// removing a node from the middle moves the last node into the freed bucket:
+/// keys of both nodes are reassigned.
+///
+/// Replicas of a key are the nodes of the buckets following the key's bucket.
+#[derive(Clone)]
+pub struct JumpHash<N, P = DefaultPartitioner> {
+    /// Partitioner used to hash keys.
+    partitioner: P,
+
+    /// Nodes, indexed by buckets.
+    nodes: Vec<N>,
+}
+
+impl<N: RingNode> Default for JumpHash<N> {
+    fn default() -> Self {
+        Self::with_partitioner(DefaultPartitioner::new())
+    }
+}
+
+impl<N: RingNode> JumpHash<N> {
+    /// Creates an empty jump hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N: RingNode, P> JumpHash<N, P> {
+    /// Creates an empty jump hash, using a given partitioner to hash keys.
+    pub const fn with_partitioner(partitioner: P) -> Self {
+        Self {
+            partitioner,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Returns the node responsible for the given key.
+    pub fn node<K: Hash>(&self, key: &K) -> Option<&N>
+    where
+        P: Partitioner<K>,
+    {
+        self.bucket(key).map(|bucket| &self.nodes[bucket])
+    }
+
+    /// Returns up to `k` nodes responsible for the given key.
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<&N>
+    where
+        P: Partitioner<K>,
+    {
+        let Some(bucket) = self.bucket(key) else {
+            return Vec::new();
+        };
+        (0..k.min(self.nodes.len()))
+            .map(|i| &self.nodes[(bucket + i) % self.nodes.len()])
+            .collect()
+    }
+
+    /// Adds a node, assigning it the next bucket.
+    pub fn add(&mut self, node: N) {
+        if !self.nodes.contains(&node) {
+            self.nodes.push(node);
+        }
+    }
+
+    /// Removes a node.
+    ///
+    /// The last node takes over the bucket of the removed node.
+    pub fn remove(&mut self, node: &N) {
+        if let Some(bucket) = self.nodes.iter().position(|n| n == node) {
+            self.nodes.swap_remove(bucket);
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the nodes, in bucket order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Returns the bucket of the given key.
+    fn bucket<K: Hash>(&self, key: &K) -> Option<usize>
+    where
+        P: Partitioner<K>,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        Some(jump(self.partitioner.position(key), self.nodes.len()))
+    }
+}
+
+impl<K, N, P> ConsistentHasher<K> for JumpHash<N, P>
+where
+    K: Hash,
+    N: RingNode + Clone,
+    P: Partitioner<K>,
+{
+    type Node = N;
+
+    fn node(&self, key: &K) -> Option<N> {
+        JumpHash::node(self, key).cloned()
+    }
+
+    fn replicas(&self, key: &K, k: usize) -> Vec<N> {
+        JumpHash::replicas(self, key, k)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn add(&mut self, node: N) {
+        JumpHash::add(self, node);
+    }
+
+    fn remove(&mut self, node: &N) {
+        JumpHash::remove(self, node);
+    }
+
+    fn len(&self) -> usize {
+        JumpHash::len(self)
+    }
+}
+
+/// Maps a key hash to one of `buckets` buckets.
+fn jump(mut key: u64, buckets: usize) -> usize {
+    let mut bucket = 0;
+    let mut next = 0;
+    while next < buckets as u64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as u64;
+    }
+    bucket as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        // Values of the reference implementation.
+        assert_eq!(jump(1, 1), 0);
+        assert_eq!(jump(42, 57), 43);
+        assert_eq!(jump(0xdead_10cc, 1), 0);
+        assert_eq!(jump(0xdead_10cc, 666), 361);
+        assert_eq!(jump(256, 1024), 520);
+        for buckets in 1..100 {
+            assert!(jump(42, buckets) < buckets);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use {
+    super::ConsistentHasher,
+    crate::{DefaultPartitioner, Partitioner, RingNode},
+    std::hash::Hash,
+};
+
+/// Default size of the Maglev lookup table.
+pub const DEFAULT_TABLE_SIZE: usize = 65537;
+
+/// Maglev hash.
+///
+/// Implements the lookup table from the
+/// [Maglev: A Fast and Reliable Software Network Load Balancer](https://research.google/pubs/pub44824/)
+/// paper: every node fills the slots of the table in the order of its own
+/// permutation, so that nodes get (almost) equal shares of the slots. Keys are
+/// mapped to slots, so lookups are `O(1)`.
+///
+/// The table is rebuilt whenever a node is added or removed. Disruption is
+/// small, but not minimal: a few keys may move between the nodes which
+/// remain. Replicas of a key are the next distinct nodes found in the
+/// following slots.
+#[derive(Clone)]
+pub struct MaglevTable<N, P = DefaultPartitioner> {
+    /// Partitioner used to hash nodes and keys.
+    partitioner: P,
+
+    /// Nodes, in the order of addition.
+    nodes: Vec<N>,
+
+    /// Lookup table, holding indexes into `nodes`.
+    table: Vec<usize>,
+
+    /// Size of the lookup table.
+    table_size: usize,
+}
+
+impl<N: RingNode> Default for MaglevTable<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: RingNode> MaglevTable<N> {
+    /// Creates an empty Maglev table of the default size.
+    pub fn new() -> Self {
+        Self::with_table_size(DEFAULT_TABLE_SIZE)
+    }
+
+    /// Creates an empty Maglev table of a given size.
+    ///
+    /// The size should be considerably larger than the number of nodes (the
+    /// paper recommends 100 times larger) for the nodes to be balanced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table_size` is not a prime number.
+    pub fn with_table_size(table_size: usize) -> Self {
+        Self::with_partitioner(DefaultPartitioner::new(), table_size)
+    }
+}
+
+impl<N: RingNode, P: Partitioner<N>> MaglevTable<N, P> {
+    /// Creates an empty Maglev table of a given size, using a given
+    /// partitioner to hash nodes and keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table_size` is not a prime number.
+    pub fn with_partitioner(partitioner: P, table_size: usize) -> Self {
+        assert!(is_prime(table_size), "table size must be a prime number");
+        Self {
+            partitioner,
+            nodes: Vec::new(),
+            table: Vec::new(),
+            table_size,
+        }
+    }
+
+    /// Returns the node responsible for the given key.
+    pub fn node<K: Hash>(&self, key: &K) -> Option<&N>
+    where
+        P: Partitioner<K>,
+    {
+        self.slot(key).map(|slot| &self.nodes[self.table[slot]])
+    }
+
+    /// Returns up to `k` distinct nodes responsible for the given key.
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<&N>
+    where
+        P: Partitioner<K>,
+    {
+        let Some(slot) = self.slot(key) else {
+            return Vec::new();
+        };
+        let k = k.min(self.nodes.len());
+        let mut indexes = Vec::with_capacity(k);
+        let slots = self.table[slot..].iter().chain(&self.table[..slot]);
+        for &index in slots {
+            if indexes.len() == k {
+                break;
+            }
+            if !indexes.contains(&index) {
+                indexes.push(index);
+            }
+        }
+        indexes
+            .into_iter()
+            .map(|index| &self.nodes[index])
+            .collect()
+    }
+
+    /// Adds a node, and rebuilds the lookup table.
+    pub fn add(&mut self, node: N) {
+        if !self.nodes.contains(&node) {
+            self.nodes.push(node);
+            self.populate();
+        }
+    }
+
+    /// Removes a node, and rebuilds the lookup table.
+    pub fn remove(&mut self, node: &N) {
+        if let Some(index) = self.nodes.iter().position(|n| n == node) {
+            self.nodes.remove(index);
+            self.populate();
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the size of the lookup table.
+    pub const fn table_size(&self) -> usize {
+        self.table_size
+    }
+
+    /// Returns the slot of the given key.
+    fn slot<K: Hash>(&self, key: &K) -> Option<usize>
+    where
+        P: Partitioner<K>,
+    {
+        if self.table.is_empty() {
+            return None;
+        }
+        Some((self.partitioner.position(key) % self.table_size as u64) as usize)
+    }
+
+    /// Fills the lookup table (see "Algorithm 1" of the paper).
+    fn populate(&mut self) {
+        self.table.clear();
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        // Permutation of each node is defined by an offset and a skip.
+        let size = self.table_size as u64;
+        let permutations = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let offset_hash = self.partitioner.position(node);
+                let skip_hash = self.partitioner.position_seeded(node, offset_hash);
+                (offset_hash % size, skip_hash % (size - 1) + 1)
+            })
+            .collect::<Vec<_>>();
+
+        let mut table = vec![usize::MAX; self.table_size];
+        let mut next = vec![0; self.nodes.len()];
+        let mut filled = 0;
+        loop {
+            for (index, (offset, skip)) in permutations.iter().enumerate() {
+                let mut slot = (offset + next[index] * skip) % size;
+                while table[slot as usize] != usize::MAX {
+                    next[index] += 1;
+                    slot = (offset + next[index] * skip) % size;
+                }
+                table[slot as usize] = index;
+                next[index] += 1;
+                filled += 1;
+                if filled == self.table_size {
+                    self.table = table;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<K, N, P> ConsistentHasher<K> for MaglevTable<N, P>
+where
+    K: Hash,
+    N: RingNode + Clone,
+    P: Partitioner<N> + Partitioner<K>,
+{
+    type Node = N;
+
+    fn node(&self, key: &K) -> Option<N> {
+        MaglevTable::node(self, key).cloned()
+    }
+
+    fn replicas(&self, key: &K, k: usize) -> Vec<N> {
+        MaglevTable::replicas(self, key, k)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn add(&mut self, node: N) {
+        MaglevTable::add(self, node);
+    }
+
+    fn remove(&mut self, node: &N) {
+        MaglevTable::remove(self, node);
+    }
+
+    fn len(&self) -> usize {
+        MaglevTable::len(self)
+    }
+}
+
+/// Checks whether a number is prime.
+fn is_prime(n: usize) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
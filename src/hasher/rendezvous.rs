@@ -0,0 +1,136 @@
+use {
+    super::ConsistentHasher,
+    crate::{DefaultPartitioner, Partitioner, RingNode, RingPosition},
+    std::{cmp::Reverse, hash::Hash},
+};
+
+/// Rendezvous (highest random weight) hash.
+///
+/// Every node is scored for a given key, and the key is assigned to the node
+/// with the highest score. Replicas of the key are the nodes with the next
+/// highest scores. See
+/// [A Name-Based Mapping Scheme for Rendezvous](https://www.eecs.umich.edu/techreports/cse/96/CSE-TR-316-96.pdf).
+///
+/// Adding or removing a node only moves the keys of that node, at the cost of
+/// `O(n)` lookups.
+#[derive(Clone)]
+pub struct RendezvousHash<N, P = DefaultPartitioner> {
+    /// Partitioner used to hash nodes and keys.
+    partitioner: P,
+
+    /// Nodes, along with their positions (used as seeds to score keys).
+    nodes: Vec<(N, RingPosition)>,
+}
+
+impl<N: RingNode> Default for RendezvousHash<N> {
+    fn default() -> Self {
+        Self::with_partitioner(DefaultPartitioner::new())
+    }
+}
+
+impl<N: RingNode> RendezvousHash<N> {
+    /// Creates an empty rendezvous hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N: RingNode, P: Partitioner<N>> RendezvousHash<N, P> {
+    /// Creates an empty rendezvous hash, using a given partitioner to hash
+    /// nodes and keys.
+    pub const fn with_partitioner(partitioner: P) -> Self {
+        Self {
+            partitioner,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Returns the node responsible for the given key.
+    pub fn node<K: Hash>(&self, key: &K) -> Option<&N>
+    where
+        P: Partitioner<K>,
+    {
+        self.nodes
+            .iter()
+            .min_by_key(|(_, seed)| Reverse(self.score(key, *seed)))
+            .map(|(node, _)| node)
+    }
+
+    /// Returns up to `k` nodes responsible for the given key, in the order of
+    /// decreasing scores.
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<&N>
+    where
+        P: Partitioner<K>,
+    {
+        let mut scored = self
+            .nodes
+            .iter()
+            .map(|(node, seed)| (self.score(key, *seed), node))
+            .collect::<Vec<_>>();
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        scored.into_iter().take(k).map(|(_, node)| node).collect()
+    }
+
+    /// Adds a node.
+    pub fn add(&mut self, node: N) {
+        if !self.nodes.iter().any(|(n, _)| *n == node) {
+            let seed = self.partitioner.position(&node);
+            self.nodes.push((node, seed));
+        }
+    }
+
+    /// Removes a node.
+    pub fn remove(&mut self, node: &N) {
+        self.nodes.retain(|(n, _)| n != node);
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the score of a key for a node with a given seed.
+    fn score<K: Hash>(&self, key: &K, seed: RingPosition) -> RingPosition
+    where
+        P: Partitioner<K>,
+    {
+        self.partitioner.position_seeded(key, seed)
+    }
+}
+
+impl<K, N, P> ConsistentHasher<K> for RendezvousHash<N, P>
+where
+    K: Hash,
+    N: RingNode + Clone,
+    P: Partitioner<N> + Partitioner<K>,
+{
+    type Node = N;
+
+    fn node(&self, key: &K) -> Option<N> {
+        RendezvousHash::node(self, key).cloned()
+    }
+
+    fn replicas(&self, key: &K, k: usize) -> Vec<N> {
+        RendezvousHash::replicas(self, key, k)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn add(&mut self, node: N) {
+        RendezvousHash::add(self, node);
+    }
+
+    fn remove(&mut self, node: &N) {
+        RendezvousHash::remove(self, node);
+    }
+
+    fn len(&self) -> usize {
+        RendezvousHash::len(self)
+    }
+}
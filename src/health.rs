@@ -0,0 +1,190 @@
+use {
+    crate::{HashRing, Partitioner, RingDirection::Clockwise, RingNode, RingPosition, RingToken},
+    std::{collections::VecDeque, hash::Hash, sync::atomic::Ordering},
+};
+
+/// Availability of a node.
+///
+/// Health is tracked separately from membership: marking a node as
+/// unavailable doesn't move its key ranges, so a transient failure doesn't
+/// reshuffle ownership. Failover lookups (see [`HashRing::node_failover`] and
+/// [`HashRing::replicas_failover`]) skip unavailable nodes, while plain
+/// lookups ignore health altogether.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeHealth {
+    /// Node is operating normally.
+    #[default]
+    Up,
+
+    /// Node is suspected to be failing, but still serves requests.
+    Suspect,
+
+    /// Node is not reachable.
+    Down,
+
+    /// Node is being decommissioned, and accepts no new requests.
+    Draining,
+}
+
+impl NodeHealth {
+    /// Returns `true` if the node can serve requests, i.e. it is either
+    /// [`Up`](Self::Up) or [`Suspect`](Self::Suspect).
+    pub const fn is_available(self) -> bool {
+        matches!(self, Self::Up | Self::Suspect)
+    }
+}
+
+/// Token selected by a failover lookup.
+#[derive(Clone, Debug)]
+pub struct FailoverToken<'a, N> {
+    /// The selected token.
+    pub token: RingToken<'a, N>,
+
+    /// Unavailable token the selected token substitutes for, `None` if the
+    /// selected token is the one a plain lookup returns.
+    pub replaces: Option<RingToken<'a, N>>,
+}
+
+impl<N> FailoverToken<'_, N> {
+    /// Returns `true` if the token substitutes for an unavailable one.
+    pub const fn is_substitute(&self) -> bool {
+        self.replaces.is_some()
+    }
+}
+
+impl<N: RingNode, P: Partitioner<N>> HashRing<N, P> {
+    /// Sets the health of a node, returning `false` if the node is not part
+    /// of the ring.
+    ///
+    /// If the node is placed at several positions (see
+    /// [`insert()`](Self::insert)), all of them are updated. Whenever health
+    /// of the node changes, the epoch is incremented (as failover lookups are
+    /// affected). Health is not a membership change though: no events are
+    /// emitted, and health is not included into the ring state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, NodeHealth};
+    ///
+    /// let ring = HashRing::new();
+    /// ring.add(1u64);
+    /// assert!(ring.set_health(&1, NodeHealth::Down));
+    /// assert_eq!(ring.health(&1), Some(NodeHealth::Down));
+    /// assert!(!ring.set_health(&2, NodeHealth::Down));
+    /// ```
+    pub fn set_health(&self, node: &N, health: NodeHealth) -> bool {
+        let _guard = self.mutation_guard();
+        let (mut found, mut changed) = (false, false);
+        for token in self.nodes().filter(|token| token.node() == node) {
+            changed |= self.health_at(token.position()) != health;
+            if health == NodeHealth::Up {
+                self.health.remove(&token.position());
+            } else {
+                self.health.insert(token.position(), health);
+            }
+            found = true;
+        }
+        if changed {
+            self.epoch.fetch_add(1, Ordering::SeqCst);
+        }
+        found
+    }
+
+    /// Returns the health of a node.
+    ///
+    /// Whenever the node is not part of the ring, `None` is returned.
+    pub fn health(&self, node: &N) -> Option<NodeHealth> {
+        self.nodes()
+            .find(|token| token.node() == node)
+            .map(|token| self.health_at(token.position()))
+    }
+
+    /// Returns the node responsible for the given key, skipping unavailable
+    /// nodes.
+    ///
+    /// Moves clockwise from the primary node (see [`node()`](Self::node))
+    /// until an available node is found. If the primary node is unavailable,
+    /// the returned token records it as the replaced one. Returns `None` if
+    /// no node is available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, NodeHealth};
+    ///
+    /// let ring = HashRing::new();
+    /// (0..5u64).for_each(|node| ring.add(node));
+    ///
+    /// let primary = ring.node(&"key").unwrap();
+    /// ring.set_health(primary.node(), NodeHealth::Down);
+    ///
+    /// let token = ring.node_failover(&"key").unwrap();
+    /// assert_ne!(token.token, primary);
+    /// assert_eq!(token.replaces, Some(primary));
+    /// ```
+    pub fn node_failover<K: Hash>(&self, key: &K) -> Option<FailoverToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        let (_, primary) = self.primary_probe(key)?;
+        let token = self
+            .tokens(primary.position(), Clockwise)
+            .find(|token| self.health_at(token.position()).is_available())?;
+        let replaces = (token != primary).then_some(primary);
+        Some(FailoverToken { token, replaces })
+    }
+
+    /// Returns `k` nodes responsible for the given key, substituting
+    /// unavailable nodes.
+    ///
+    /// Available nodes among the ones returned by
+    /// [`replicas()`](Self::replicas) are kept. Each unavailable one is
+    /// replaced by the next available node further clockwise, and the
+    /// substitutes record which node they replace. Tokens are returned in the
+    /// clockwise order. Fewer than `k` tokens are returned if the ring runs
+    /// out of available nodes.
+    pub fn replicas_failover<K: Hash>(&self, key: &K, k: usize) -> Vec<FailoverToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        let Some((_, primary)) = self.primary_probe(key) else {
+            return Vec::new();
+        };
+
+        let mut replicas = Vec::with_capacity(k);
+        let mut unavailable = VecDeque::new();
+        for (i, token) in self.tokens(primary.position(), Clockwise).enumerate() {
+            let available = self.health_at(token.position()).is_available();
+            if i < k {
+                if available {
+                    replicas.push(FailoverToken {
+                        token,
+                        replaces: None,
+                    });
+                } else {
+                    unavailable.push_back(token);
+                }
+            } else if unavailable.is_empty() {
+                break;
+            } else if available {
+                replicas.push(FailoverToken {
+                    token,
+                    replaces: unavailable.pop_front(),
+                });
+            }
+        }
+        replicas
+    }
+
+    /// Returns the health of the node at a given position.
+    fn health_at(&self, pos: RingPosition) -> NodeHealth {
+        if self.health.is_empty() {
+            return NodeHealth::Up;
+        }
+        self.health
+            .get(&pos)
+            .map_or(NodeHealth::Up, |entry| *entry.value())
+    }
+}
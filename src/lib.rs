@@ -1,32 +1,84 @@
 #![doc = include_str!("../README.md")]
 #![forbid(unsafe_code)]
 
+mod batch;
+mod bounded;
+mod builder;
+mod diff;
+mod error;
+mod event;
+mod hasher;
+mod health;
 mod iter;
 mod partitioner;
 mod range;
+mod rebalance;
+mod snapshot;
+mod state;
+mod stats;
+mod storage;
 mod token;
+mod wire;
+mod zone;
 
 use {
     crate::{
+        event::Subscriber,
         iter::HashRingIter,
+        storage::Positions,
         RingDirection::{Clockwise, CounterClockwise},
     },
     crossbeam_skiplist::SkipMap,
     std::{
         hash::Hash,
         ops::Bound::{Excluded, Unbounded},
-        sync::Arc,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+            PoisonError,
+            RwLock,
+            RwLockReadGuard,
+        },
     },
 };
-pub use {partitioner::*, range::*, token::RingToken};
+pub use {
+    bounded::BoundedLoadRing,
+    builder::HashRingBuilder,
+    diff::{RegionChange, RingDiff},
+    error::{CollisionError, StateError, WireError},
+    event::RingEvent,
+    hasher::{
+        AnchorHash,
+        ConsistentHasher,
+        JumpHash,
+        MaglevTable,
+        RendezvousHash,
+        DEFAULT_TABLE_SIZE,
+    },
+    health::{FailoverToken, NodeHealth},
+    partitioner::*,
+    range::*,
+    rebalance::{RebalancePlan, Transfer},
+    snapshot::RingSnapshot,
+    state::{RingState, TokenState},
+    stats::{LoadStats, NodeStats},
+    storage::{RingStorage, SkipMapStorage, SortedArrayStorage},
+    token::{OwnedRingToken, RingToken},
+    wire::{NodeCodec, RawCodec, WirePartitioner, WIRE_FORMAT_VERSION},
+    zone::ZonedNode,
+};
 
 /// Node that serves as a destination for data.
 ///
 /// Node controls one or more interval of the key space.
 /// Keys which fall into such an interval are routed to the node.
-pub trait RingNode: Hash + Send + 'static {}
+///
+/// Equality is used to tell nodes apart, whenever different nodes are
+/// assigned the same ring position. Nodes are shared between threads, which
+/// concurrently modify the ring, hence `Sync`.
+pub trait RingNode: Hash + PartialEq + Send + Sync + 'static {}
 
-impl<T> RingNode for T where T: Hash + Send + 'static {}
+impl<T> RingNode for T where T: Hash + PartialEq + Send + Sync + 'static {}
 
 /// Number of probing attempts before selecting key's position on the ring.
 ///
@@ -35,11 +87,20 @@ impl<T> RingNode for T where T: Hash + Send + 'static {}
 /// owner.
 pub const DEFAULT_PROBE_COUNT: usize = 23;
 
+/// Maximum number of attempts to find a free ring position for a node.
+///
+/// See [`HashRing::try_add`] for details.
+const MAX_PLACEMENT_ATTEMPTS: usize = 16;
+
+/// Weight of a node added without explicitly specifying it.
+pub const DEFAULT_WEIGHT: u32 = 1;
+
 /// Position on the ring.
 pub type RingPosition = u64;
 
 /// Defines the direction in which the ring is traversed.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RingDirection {
     Clockwise,
     CounterClockwise,
@@ -49,26 +110,45 @@ pub enum RingDirection {
 ///
 /// Nodes are assigned positions on the ring, effectively becoming responsible
 /// for a range of keys: from the previous node (counter-clockwise) up to and
-/// not including the node's position.
+/// not including the node's position. So, a key (or a probe) landing exactly
+/// on the position of a node is owned by the next node.
+///
+/// Use [`HashRingBuilder`] to create a ring with non-default probe count,
+/// partitioner or storage.
 #[derive(Clone)]
 pub struct HashRing<N: RingNode, P = DefaultPartitioner> {
     /// Partitioner used to compute ring positions.
     partitioner: P,
 
-    /// The ring positions assigned to nodes (sorted in ascending order).
-    positions: Arc<SkipMap<RingPosition, N>>,
+    /// The ring positions assigned to nodes (sorted in ascending order), along
+    /// with the nodes and their weights.
+    positions: Arc<Positions<N>>,
+
+    /// Health of the nodes, keyed by node positions.
+    ///
+    /// Only nodes which are not [`NodeHealth::Up`] are tracked.
+    health: Arc<SkipMap<RingPosition, NodeHealth>>,
 
     /// The number of positions to probe for a given key.
     probe_count: usize,
+
+    /// Version of the ring, incremented on every modification.
+    epoch: Arc<AtomicU64>,
+
+    /// Guards consistency of snapshots.
+    ///
+    /// Modifications hold a shared lock (so they don't block each other),
+    /// while snapshots hold an exclusive one (so no modification is observed
+    /// half-applied).
+    mutations: Arc<RwLock<()>>,
+
+    /// Callbacks notified on membership changes.
+    subscribers: Arc<RwLock<Vec<Subscriber<N>>>>,
 }
 
 impl<N: RingNode> Default for HashRing<N> {
     fn default() -> Self {
-        Self {
-            partitioner: DefaultPartitioner::new(),
-            positions: Arc::new(SkipMap::new()),
-            probe_count: DEFAULT_PROBE_COUNT,
-        }
+        HashRingBuilder::new().build()
     }
 }
 
@@ -102,6 +182,18 @@ impl<N: RingNode> HashRing<N> {
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<N: RingNode, P: Partitioner<N>> HashRing<N, P> {
+    /// Returns the number of positions probed for a given key.
+    pub fn probe_count(&self) -> usize {
+        self.probe_count
+    }
+
+    /// Returns the partitioner used to compute ring positions.
+    pub fn partitioner(&self) -> &P {
+        &self.partitioner
+    }
 
     /// Inserts a node to a given ring position.
     ///
@@ -117,19 +209,191 @@ impl<N: RingNode> HashRing<N> {
     /// ring.insert(1, 16);
     /// ```
     pub fn insert(&self, pos: RingPosition, node: N) {
-        self.positions.insert(pos, node);
+        self.insert_weighted(pos, node, DEFAULT_WEIGHT);
     }
 
     /// Adds a new node to the ring.
     ///
     /// The position is computed deterministically using keyspace partitioner.
+    /// Whenever the position is already taken by a different node, the next
+    /// candidate position is tried (see [`try_add()`](Self::try_add)). If no
+    /// free position is found, the node is not added.
     pub fn add(&self, node: N) {
-        let pos = self.partitioner.position(&node);
-        self.positions.insert(pos, node);
+        self.add_weighted(node, DEFAULT_WEIGHT);
+    }
+
+    /// Adds a new node with a given weight to the ring.
+    ///
+    /// The expected share of keys owned by the node is proportional to its
+    /// weight, i.e. a node of weight `2` gets twice as many keys as a node of
+    /// weight `1`. No virtual nodes are created: when probing, the distance
+    /// from a probe to a node is scaled down by the node's weight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.add(1);
+    /// ring.add_weighted(2, 3);
+    /// assert_eq!(ring.weight(&1), Some(1));
+    /// assert_eq!(ring.weight(&2), Some(3));
+    /// ```
+    pub fn add_weighted(&self, node: N, weight: u32) {
+        let _ = self.try_add_weighted(node, weight);
+    }
+
+    /// Adds a new node to the ring, returning the assigned position.
+    ///
+    /// Normally, the node is placed at [`position()`](Self::position) of the
+    /// node. Whenever that position is already taken by a different node,
+    /// the node is re-seeded: the next candidate position is computed using
+    /// [`Partitioner::position_seeded`], with the previous candidate position
+    /// serving as a seed. So, colliding nodes coexist on the ring, and
+    /// positions of both are deterministic (given the order of additions).
+    ///
+    /// Adding a node, which is already on the ring, is a no-op (except for
+    /// resetting its weight), and its current position is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollisionError`] if no free position is found after several
+    /// attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// let pos = ring.try_add(1).expect("no collision");
+    /// assert_eq!(pos, ring.position(&1u64));
+    ///
+    /// // Colliding node is placed at the next candidate position.
+    /// ring.insert(ring.position(&2u64), 3);
+    /// let pos = ring.try_add(2).expect("collision resolved");
+    /// assert_ne!(pos, ring.position(&2u64));
+    /// assert_eq!(ring.len(), 3);
+    /// ```
+    pub fn try_add(&self, node: N) -> Result<RingPosition, CollisionError> {
+        self.try_add_weighted(node, DEFAULT_WEIGHT)
+    }
+
+    /// Adds a new node with a given weight to the ring, returning the assigned
+    /// position.
+    ///
+    /// See [`try_add()`](Self::try_add) and
+    /// [`add_weighted()`](Self::add_weighted) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CollisionError`] if no free position is found after several
+    /// attempts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is zero.
+    pub fn try_add_weighted(&self, node: N, weight: u32) -> Result<RingPosition, CollisionError> {
+        assert!(weight > 0, "node weight must be positive");
+        if let Some(pos) = self.position_of(&node) {
+            self.insert_weighted(pos, node, weight);
+            return Ok(pos);
+        }
+
+        let position = self.partitioner.position(&node);
+        let candidates = self.candidate_positions(&node).collect::<Vec<_>>();
+        let mut node = node;
+        for pos in candidates {
+            // Position is checked and taken in a single step, so that nodes
+            // added concurrently never replace each other.
+            node = match self.try_insert_weighted(pos, node, weight) {
+                Ok(()) => return Ok(pos),
+                Err(node) => node,
+            };
+            // The same node might have been added concurrently.
+            if self
+                .positions
+                .get(pos)
+                .is_some_and(|token| *token.node() == node)
+            {
+                self.insert_weighted(pos, node, weight);
+                return Ok(pos);
+            }
+        }
+        Err(CollisionError { position })
+    }
+
+    /// Places a node with a given weight to a given ring position, unless the
+    /// position is already taken, in which case the node is given back.
+    fn try_insert_weighted(&self, pos: RingPosition, node: N, weight: u32) -> Result<(), N> {
+        let guard = self.mutation_guard();
+        let token = self.positions.try_insert(pos, node, weight)?;
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst) + 1;
+        let range = self.key_range(pos).filter(|_| self.has_subscribers());
+        drop(guard);
+
+        if let Some(range) = range {
+            self.notify(&RingEvent::NodeAdded {
+                node: token.node(),
+                position: pos,
+                range,
+                epoch,
+            });
+        }
+        Ok(())
+    }
+
+    /// Inserts a node with a given weight to a given ring position.
+    fn insert_weighted(&self, pos: RingPosition, node: N, weight: u32) {
+        assert!(weight > 0, "node weight must be positive");
+        let guard = self.mutation_guard();
+        let (token, mut replaced) = self.positions.insert(pos, node, weight);
+        // Re-inserting the same node (e.g. with a different weight) is not a
+        // membership change.
+        let reinserted = replaced.iter().any(|old| old.node() == token.node());
+        replaced.retain(|old| old.node() != token.node());
+        if !replaced.is_empty() {
+            self.health.remove(&pos);
+        }
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst) + 1;
+        // Replacing a node doesn't change the key range of the position.
+        let range = self.key_range(pos).filter(|_| self.has_subscribers());
+        drop(guard);
+
+        let Some(range) = range else {
+            return;
+        };
+        for old in replaced {
+            self.notify(&RingEvent::NodeRemoved {
+                node: old.node(),
+                position: pos,
+                range: range.clone(),
+                epoch,
+            });
+        }
+        if !reinserted {
+            self.notify(&RingEvent::NodeAdded {
+                node: token.node(),
+                position: pos,
+                range,
+                epoch,
+            });
+        }
+    }
+
+    /// Returns the weight of a given node.
+    ///
+    /// Whenever the node is not part of the ring, `None` is returned.
+    pub fn weight(&self, node: &N) -> Option<u32> {
+        self.position_of(node).map(|pos| self.weight_at(pos))
     }
 
     /// Removes a node from the ring.
     ///
+    /// Only the given node is removed, even if some other node collides with
+    /// it (see [`try_add()`](Self::try_add)).
+    ///
     /// # Examples
     ///
     /// ```
@@ -138,21 +402,261 @@ impl<N: RingNode> HashRing<N> {
     /// ring.remove(&42);
     /// ```
     pub fn remove(&self, node: &N) {
-        let pos = self.partitioner.position(node);
-        self.positions.remove(&pos);
+        let token = self
+            .candidate_positions(node)
+            .filter_map(|pos| self.positions.get(pos))
+            .find(|token| token.node() == node);
+        if let Some(token) = token {
+            self.remove_token(&token);
+        }
+    }
+
+    /// Removes the node located at a given position, returning its token.
+    ///
+    /// Unlike [`remove()`](Self::remove), also works for nodes placed with
+    /// [`insert()`](Self::insert). Whenever no node is located at the
+    /// position, or the node is concurrently removed or replaced, `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.insert(10, 1);
+    ///
+    /// let token = ring.remove_at(10).unwrap();
+    /// assert_eq!(token.node(), &1);
+    /// assert!(ring.remove_at(10).is_none());
+    /// ```
+    pub fn remove_at(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        let token = self.positions.get(pos)?;
+        self.remove_token(&token).then_some(token)
+    }
+
+    /// Removes all the nodes from the ring, returning the number of removed
+    /// nodes.
+    ///
+    /// Nodes are removed one by one, so subscribers are notified of every
+    /// removal. Nodes removed concurrently (by other calls) are not counted.
+    pub fn clear(&self) -> usize {
+        self.positions
+            .iter()
+            .filter(|token| self.remove_token(token))
+            .count()
+    }
+
+    /// Removes a given token, and notifies subscribers.
+    ///
+    /// Returns `false` (and changes nothing) if the token was already removed
+    /// or replaced, e.g. concurrently.
+    fn remove_token(&self, token: &RingToken<'_, N>) -> bool {
+        let pos = token.position();
+        let guard = self.mutation_guard();
+        let range = self.key_range(pos).filter(|_| self.has_subscribers());
+        if !self.positions.remove(token) {
+            return false;
+        }
+        self.health.remove(&pos);
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst) + 1;
+        drop(guard);
+
+        if let Some(range) = range {
+            self.notify(&RingEvent::NodeRemoved {
+                node: token.node(),
+                position: pos,
+                range,
+                epoch,
+            });
+        }
+        true
+    }
+
+    /// Returns the token of the node located at a given position.
+    ///
+    /// Whenever no node is located at the position, `None` is returned. See
+    /// [`node()`](Self::node) for finding the node owning a position.
+    pub fn get(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        self.positions.get(pos)
+    }
+
+    /// Returns the token of a given node.
+    ///
+    /// Whenever the node is not part of the ring, `None` is returned. If the
+    /// node is placed at several positions (see [`insert()`](Self::insert)),
+    /// the token at its computed position is preferred, otherwise the first
+    /// one (in the order of positions) is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// let pos = ring.try_add(1).unwrap();
+    /// ring.insert(10, 2);
+    ///
+    /// assert_eq!(ring.token_of(&1).unwrap().position(), pos);
+    /// assert_eq!(ring.token_of(&2).unwrap().position(), 10);
+    /// assert!(ring.token_of(&3).is_none());
+    /// ```
+    pub fn token_of(&self, node: &N) -> Option<RingToken<'_, N>> {
+        self.position_of(node)
+            .and_then(|pos| self.positions.get(pos))
+            .or_else(|| self.positions.iter().find(|token| token.node() == node))
+    }
+
+    /// Returns `true` if a given node is part of the ring.
+    pub fn contains(&self, node: &N) -> bool {
+        self.token_of(node).is_some()
+    }
+
+    /// Returns tokens of all the nodes, in the order of positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.insert(20, 2);
+    /// ring.insert(10, 1);
+    ///
+    /// let nodes = ring.nodes().map(|token| *token.node()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, vec![1, 2]);
+    /// ```
+    pub fn nodes(&self) -> impl DoubleEndedIterator<Item = RingToken<'_, N>> {
+        self.positions.iter()
+    }
+
+    /// Returns the position a given node is located at.
+    ///
+    /// Whenever the node is not part of the ring, `None` is returned.
+    fn position_of(&self, node: &N) -> Option<RingPosition> {
+        self.candidate_positions(node).find(|pos| {
+            self.positions
+                .get(*pos)
+                .is_some_and(|token| token.node() == node)
+        })
+    }
+
+    /// Returns tokens of all the positions a given node is located at, in the
+    /// order of positions.
+    fn tokens_of<'a>(&'a self, node: &'a N) -> impl Iterator<Item = RingToken<'a, N>> + 'a {
+        self.positions
+            .iter()
+            .filter(move |token| token.node() == node)
+    }
+
+    /// Returns sequence of positions a node can be placed at.
+    ///
+    /// The first candidate is the node's position, each next candidate is
+    /// obtained by re-seeding the partitioner with the previous candidate.
+    fn candidate_positions<'a>(&'a self, node: &'a N) -> impl Iterator<Item = RingPosition> + 'a {
+        std::iter::successors(Some(self.partitioner.position(node)), move |pos| {
+            Some(self.partitioner.position_seeded(node, *pos))
+        })
+        .take(MAX_PLACEMENT_ATTEMPTS)
     }
 
     /// Returns `k` nodes responsible for the given key.
     ///
-    /// The first node is the primary node responsible for the key. It is
-    /// guaranteed that the first node is the same as the one returned by
-    /// [`node()`](Self::node).
-    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>> {
-        self.tokens(self.position(key), Clockwise)
+    /// Nodes are collected by moving clockwise from the winning probe position
+    /// of the key (see [`probe_position()`](Self::probe_position)). Thus, the
+    /// first node is the primary node responsible for the key, and it is
+    /// guaranteed to be the same as the one returned by [`node()`](Self::node).
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        let Some((_, primary)) = self.primary_probe(key) else {
+            return Vec::new();
+        };
+        self.tokens(primary.position(), Clockwise)
             .take(k)
             .collect::<Vec<_>>()
     }
 
+    /// Returns `k` nodes responsible for the given key, as owned tokens.
+    ///
+    /// See [`replicas()`](Self::replicas) and [`RingToken::into_owned`].
+    pub fn replicas_owned<K: Hash>(&self, key: &K, k: usize) -> Vec<OwnedRingToken<N>>
+    where
+        N: Clone,
+        P: Partitioner<K>,
+    {
+        self.replicas(key, k)
+            .into_iter()
+            .map(RingToken::into_owned)
+            .collect()
+    }
+
+    /// Returns `k` distinct physical nodes responsible for the given key.
+    ///
+    /// The same node can be placed at several positions (see
+    /// [`insert()`](Self::insert)), in which case
+    /// [`replicas()`](Self::replicas) may return it more than once. This
+    /// method walks the ring in the same order, but skips tokens of the
+    /// nodes that are already selected (nodes are compared for equality).
+    /// Fewer than `k` tokens are returned only if the ring has fewer than
+    /// `k` distinct nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::HashRing;
+    ///
+    /// let ring = HashRing::new();
+    /// ring.insert(10, "node1");
+    /// ring.insert(20, "node1");
+    /// ring.insert(30, "node2");
+    ///
+    /// let replicas = ring.replicas_distinct(&"key", 3);
+    /// assert_eq!(replicas.len(), 2);
+    /// assert_ne!(replicas[0].node(), replicas[1].node());
+    /// ```
+    pub fn replicas_distinct<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.replicas_distinct_with(key, k, |a, b| a == b)
+    }
+
+    /// Returns `k` nodes responsible for the given key, with distinct physical
+    /// identities (as returned by `id`).
+    ///
+    /// Useful when different node values refer to the same machine (e.g.
+    /// nodes identified by address and disk). See
+    /// [`replicas_distinct()`](Self::replicas_distinct) for details.
+    pub fn replicas_distinct_by<K, I, F>(&self, key: &K, k: usize, id: F) -> Vec<RingToken<'_, N>>
+    where
+        K: Hash,
+        P: Partitioner<K>,
+        I: PartialEq,
+        F: Fn(&N) -> I,
+    {
+        self.replicas_distinct_with(key, k, |a, b| id(a) == id(b))
+    }
+
+    /// Collects replicas of the key, skipping nodes for which `same` holds
+    /// with some already selected node.
+    fn replicas_distinct_with<K, F>(&self, key: &K, k: usize, same: F) -> Vec<RingToken<'_, N>>
+    where
+        K: Hash,
+        P: Partitioner<K>,
+        F: Fn(&N, &N) -> bool,
+    {
+        let Some((_, primary)) = self.primary_probe(key) else {
+            return Vec::new();
+        };
+
+        let mut replicas: Vec<RingToken<'_, N>> = Vec::with_capacity(k);
+        for token in self.tokens(primary.position(), Clockwise) {
+            if replicas.len() == k {
+                break;
+            }
+            if !replicas.iter().any(|r| same(r.node(), token.node())) {
+                replicas.push(token);
+            }
+        }
+        replicas
+    }
+
     /// Returns intervals of the key space controlled by the given node.
     ///
     /// This method is necessary to re-balance the key space. When a node is
@@ -160,10 +664,63 @@ impl<N: RingNode> HashRing<N> {
     /// In order to do so, the current intervals controlled by the node need
     /// to be known.
     ///
+    /// Under multi-probe hashing, intervals describe positions of probes (and
+    /// not of keys themselves) captured by the node: a key is owned by the
+    /// node if and only if its winning probe position (see
+    /// [`probe_position()`](Self::probe_position)) falls into one of the
+    /// returned intervals. Note that the plain position of a key (see
+    /// [`position()`](Self::position)) may fall into an interval of one node,
+    /// while the key is owned by another one. To check ownership of a
+    /// particular key, use [`owns()`](Self::owns).
+    ///
+    /// A node placed at several positions (see [`insert()`](Self::insert))
+    /// controls one interval per position, ordered by their end position.
     /// Whenever the node is not part of the key space, `None` is returned.
     pub fn intervals(&self, node: &N) -> Option<Vec<KeyRange<RingPosition>>> {
-        let pos = self.position(node);
-        self.key_range(pos).map(|range| vec![range])
+        let intervals = self
+            .tokens_of(node)
+            .filter_map(|token| self.key_range(token.position()))
+            .collect::<Vec<_>>();
+        (!intervals.is_empty()).then_some(intervals)
+    }
+
+    /// Returns the ownership map of the whole key space.
+    ///
+    /// The ring is split into non-overlapping intervals (ordered by their end
+    /// position), each one coupled with the token of the node capturing all
+    /// the probes landing in that interval. Together intervals cover the
+    /// whole ring. See [`intervals()`](Self::intervals) for details.
+    ///
+    /// On an empty ring, an empty map is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.insert(10, 1);
+    /// ring.insert(20, 2);
+    ///
+    /// let map = ring.ownership();
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!((map[0].0.start, map[0].0.end, *map[0].1), (20, 10, 1));
+    /// assert_eq!((map[1].0.start, map[1].0.end, *map[1].1), (10, 20, 2));
+    /// ```
+    pub fn ownership(&self) -> Vec<(KeyRange<RingPosition>, RingToken<'_, N>)> {
+        self.tokens(0, Clockwise)
+            .filter_map(|token| self.key_range(token.position()).map(|range| (range, token)))
+            .collect()
+    }
+
+    /// Returns `true` if the given node is the primary owner of the key.
+    ///
+    /// Equivalent to checking that [`node()`](Self::node) returns the given
+    /// node, whichever of its positions captures the key.
+    pub fn owns<K: Hash>(&self, node: &N, key: &K) -> bool
+    where
+        P: Partitioner<K>,
+    {
+        self.primary_token(key)
+            .is_some_and(|token| token.node() == node)
     }
 
     /// Returns ring position to which a given key will be assigned.
@@ -176,7 +733,10 @@ impl<N: RingNode> HashRing<N> {
     /// // Find the position of the key on the ring.
     /// let pos = ring.position(&key);
     /// ```
-    pub fn position<K: Hash>(&self, key: &K) -> RingPosition {
+    pub fn position<K: Hash>(&self, key: &K) -> RingPosition
+    where
+        P: Partitioner<K>,
+    {
         self.partitioner.position(key)
     }
 
@@ -185,10 +745,56 @@ impl<N: RingNode> HashRing<N> {
     /// Due to replication, a key may land on several nodes, but the primary
     /// destination is the node controlling ring position coming immediately
     /// after the key.
-    pub fn node<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>> {
+    ///
+    /// Note that a winning probe landing exactly on the position of a node is
+    /// owned by the next node (see [`key_range()`](Self::key_range)). Up to
+    /// version 2.0.9, such a probe was owned by the node located at the probed
+    /// position.
+    pub fn node<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
         self.primary_token(key)
     }
 
+    /// Returns the primary node responsible for the given key, as an owned
+    /// token.
+    ///
+    /// See [`node()`](Self::node) and [`RingToken::into_owned`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.add(1);
+    ///
+    /// let token = ring.node_owned(&"key").unwrap();
+    /// std::thread::spawn(move || assert_eq!(token.node(), &1))
+    ///     .join()
+    ///     .unwrap();
+    /// ```
+    pub fn node_owned<K: Hash>(&self, key: &K) -> Option<OwnedRingToken<N>>
+    where
+        N: Clone,
+        P: Partitioner<K>,
+    {
+        self.node(key).map(RingToken::into_owned)
+    }
+
+    /// Returns the winning probe position for the given key.
+    ///
+    /// Out of all the probed positions of the key, the one closest to the next
+    /// node (clockwise) is selected. The primary node and replicas for the key
+    /// are found by moving clockwise from that position.
+    ///
+    /// Whenever the ring is empty, `None` is returned.
+    pub fn probe_position<K: Hash>(&self, key: &K) -> Option<RingPosition>
+    where
+        P: Partitioner<K>,
+    {
+        self.primary_probe(key).map(|(pos, _)| pos)
+    }
+
     /// Returns the token of a node that owns a range for the given key.
     ///
     /// A token is a pair of a ring position of a node and a node itself.
@@ -199,21 +805,42 @@ impl<N: RingNode> HashRing<N> {
     ///
     /// Double hashing is used to avoid non-uniform distribution of keys across
     /// the ring. From the multiple produced positions, the one with the
-    /// minimal distance to the next node is selected.
-    fn primary_token<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>> {
+    /// minimal distance to the next node is selected (distances are divided by
+    /// weights of the corresponding nodes).
+    fn primary_token<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.primary_probe(key).map(|(_, token)| token)
+    }
+
+    /// Returns the winning probe position for the given key, together with the
+    /// token of the node owning that position.
+    fn primary_probe<K: Hash>(&self, key: &K) -> Option<(RingPosition, RingToken<'_, N>)>
+    where
+        P: Partitioner<K>,
+    {
         let mut min_distance = RingPosition::MAX;
-        let mut min_token = None;
+        let mut min_weight = DEFAULT_WEIGHT;
+        let mut min_probe = None;
 
         // Calculate several positions for the given key and select the one with the
-        // minimal distance to the owner.
+        // minimal (weighted) distance to the owner.
+        let view = self.positions.view();
         for pos in self.partitioner.positions(key, self.probe_count) {
             // Find the peer that owns the position, and calculate the distance to it.
-            match self.tokens(pos, Clockwise).next() {
-                Some(token) => {
-                    let distance = distance(pos, token.position());
-                    if distance < min_distance {
+            match view.owner(pos) {
+                Some(owner) => {
+                    let distance = distance(pos, owner.position());
+                    let weight = view.weight(&owner);
+                    // Compare `distance / weight < min_distance / min_weight`, without
+                    // losing precision.
+                    let closer = u128::from(distance) * u128::from(min_weight)
+                        < u128::from(min_distance) * u128::from(weight);
+                    if min_probe.is_none() || closer {
                         min_distance = distance;
-                        min_token = Some(token);
+                        min_weight = weight;
+                        min_probe = Some((pos, owner));
                     }
                 }
                 None => {
@@ -222,7 +849,25 @@ impl<N: RingNode> HashRing<N> {
             };
         }
 
-        min_token
+        min_probe.map(|(pos, owner)| (pos, view.token(owner)))
+    }
+
+    /// Returns the weight of a node located at the given position.
+    fn weight_at(&self, pos: RingPosition) -> u32 {
+        self.positions.weight(pos)
+    }
+
+    /// Returns the sum of weights of all the nodes.
+    fn total_weight(&self) -> u64 {
+        self.positions.total_weight()
+    }
+
+    /// Returns the token of a node owning the given position.
+    ///
+    /// Consistently with [`key_range()`](Self::key_range), the owner is the
+    /// first node located strictly after the position (when moving clockwise).
+    fn owner(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        self.positions.owner(pos)
     }
 
     /// Returns assigned node positions (tokens) starting from the given
@@ -233,8 +878,27 @@ impl<N: RingNode> HashRing<N> {
     /// position nodes on a ring, when maximum position is reached, the next
     /// position is the minimum one (positions wrap around). Hence, we chain
     /// another iterator, to account for this semantics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, RingDirection};
+    ///
+    /// let ring = HashRing::<u64>::new();
+    /// ring.insert(10, 1);
+    /// ring.insert(20, 2);
+    /// ring.insert(30, 3);
+    ///
+    /// let positions = |dir| {
+    ///     ring.tokens(20, dir)
+    ///         .map(|token| token.position())
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(positions(RingDirection::Clockwise), vec![20, 30, 10]);
+    /// assert_eq!(positions(RingDirection::CounterClockwise), vec![20, 10, 30]);
+    /// ```
     #[must_use]
-    fn tokens(
+    pub fn tokens(
         &self,
         start: RingPosition,
         dir: RingDirection,
@@ -253,7 +917,6 @@ impl<N: RingNode> HashRing<N> {
                     .chain(self.positions.range((Excluded(start), Unbounded)).rev()),
             ),
         }
-        .map(Into::into)
     }
 
     /// Returns the key space range owned by a node, if it was located at given
@@ -309,6 +972,83 @@ impl<N: RingNode> HashRing<N> {
     pub fn is_empty(&self) -> bool {
         self.positions.is_empty()
     }
+
+    /// Returns the version of the ring.
+    ///
+    /// Epoch is incremented on every modification of the ring (including
+    /// changes of node health, see [`set_health()`](Self::set_health)), and is
+    /// shared by all the clones of the ring.
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::SeqCst)
+    }
+
+    /// Returns an immutable snapshot of the current state of the ring.
+    ///
+    /// Subsequent modifications of the ring are not visible in the snapshot,
+    /// so several lookups can be made against the same ring state (epoch).
+    ///
+    /// Taking a snapshot is not free: node positions are copied (`O(n)`) while
+    /// all the modifications of the ring are blocked. With
+    /// [`SortedArrayStorage`], the positions are shared with the snapshot
+    /// instead, and only the weights and health states are copied. So, rather
+    /// than taking a snapshot per request, take one whenever the ring changes
+    /// (see [`epoch()`](Self::epoch) and [`subscribe()`](Self::subscribe)),
+    /// and share it between requests (snapshots are cheap to clone).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.add(1);
+    ///
+    /// let snapshot = ring.snapshot();
+    /// ring.add(2);
+    ///
+    /// assert_eq!(snapshot.len(), 1);
+    /// assert_eq!(ring.len(), 2);
+    /// assert!(snapshot.epoch() < ring.epoch());
+    /// ```
+    pub fn snapshot(&self) -> RingSnapshot<N, P>
+    where
+        N: Clone,
+        P: Clone,
+    {
+        RingSnapshot::new(self.duplicate())
+    }
+
+    /// Returns a deep copy of the ring.
+    ///
+    /// Unlike clones, the copy doesn't share state with the original ring. The
+    /// copy starts at the epoch of the original ring.
+    fn duplicate(&self) -> Self
+    where
+        N: Clone,
+        P: Clone,
+    {
+        let mut ring = HashRingBuilder::new()
+            .partitioner(self.partitioner.clone())
+            .probe_count(self.probe_count)
+            .build();
+
+        // No modifications are allowed while copying.
+        let _guard = self
+            .mutations
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        ring.positions = Arc::new(self.positions.duplicate());
+        for entry in &*self.health {
+            ring.health.insert(*entry.key(), *entry.value());
+        }
+        ring.epoch.store(self.epoch(), Ordering::SeqCst);
+        ring
+    }
+
+    /// Returns a guard, which must be held while modifying the ring.
+    fn mutation_guard(&self) -> RwLockReadGuard<'_, ()> {
+        self.mutations
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 /// Calculates distance between two ring positions.
@@ -446,6 +1186,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn owner() {
+        let ring = HashRing::new();
+        let node1 = Node::random();
+        let node2 = Node::random();
+        ring.insert(10, node1);
+        ring.insert(20, node2);
+
+        // Position of a node belongs to the next node (clockwise).
+        let test_cases = vec![
+            (0, node1),
+            (9, node1),
+            (10, node2),
+            (19, node2),
+            (20, node1),
+            (u64::MAX, node1),
+        ];
+        for (pos, expected) in test_cases {
+            assert_eq!(ring.owner(pos).as_deref(), Some(&expected));
+            assert!(ring
+                .key_range(ring.owner(pos).unwrap().position())
+                .unwrap()
+                .contains(&pos));
+        }
+    }
+
     #[test]
     fn tokens_for_key() {
         let ring = HashRing::new();
@@ -1,7 +1,27 @@
+#[cfg(feature = "blake3")]
+mod blake;
+#[cfg(feature = "fnv")]
+mod fnv1a;
+mod keyed;
+#[cfg(feature = "murmur3")]
+mod murmur;
+mod seeded;
+#[cfg(feature = "siphash")]
+mod sip;
+
+#[cfg(feature = "blake3")]
+pub use blake::Blake3Partitioner;
+#[cfg(feature = "fnv")]
+pub use fnv1a::Fnv1aPartitioner;
+pub use keyed::{KeyedPartitioner, SecretKey};
+#[cfg(feature = "murmur3")]
+pub use murmur::Murmur3Partitioner;
+#[cfg(feature = "siphash")]
+pub use sip::SipHashPartitioner;
 use {
-    crate::RingPosition,
+    crate::{RingPosition, WirePartitioner},
     hash_iter::{DoubleHashHasher, HashIterHasher},
-    std::hash::{BuildHasher, Hash},
+    std::hash::{BuildHasher, Hash, Hasher},
     xxhash_rust::xxh3::Xxh3Builder,
 };
 
@@ -32,39 +52,196 @@ pub const DEFAULT_SEED1: u64 = 12345;
 /// The second seed for double hashing.
 pub const DEFAULT_SEED2: u64 = 67890;
 
+/// Seeds of the hashers producing the probe sequence of the default
+/// partitioner.
+///
+/// See [`Xxh3Partitioner::with_probe_seeds`].
+pub const DEFAULT_PROBE_SEEDS: [u64; 2] = [0, 0];
+
 /// A partitioner that uses a XXH3 hash function to partition data.
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PartitionerRepr", into = "PartitionerRepr")
+)]
 pub struct Xxh3Partitioner {
     hash_builder: Xxh3Builder,
     hash_iter: DoubleHashHasher,
+    seeds: Xxh3Seeds,
+}
+
+/// Seeds of the XXH3 partitioner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Xxh3Seeds {
+    /// Seed used to compute the main position of a key.
+    seed: RingPosition,
+
+    /// Seeds of the hashers producing the probe sequence.
+    probe_seeds: [RingPosition; 2],
 }
 
 impl Default for Xxh3Partitioner {
     fn default() -> Self {
+        // Probes are produced by unseeded hashers, which is kept as is, so that
+        // key placement stays stable across crate versions.
+        Self::with_probe_seeds(DEFAULT_SEED1, DEFAULT_PROBE_SEEDS)
+    }
+}
+
+impl From<Xxh3Seeds> for Xxh3Partitioner {
+    fn from(seeds: Xxh3Seeds) -> Self {
+        let [probe_seed1, probe_seed2] = seeds.probe_seeds;
         Self {
             hash_builder: Xxh3Builder::new(),
             hash_iter: DoubleHashHasher::with_hash_builders(
-                Xxh3Builder::new(),
-                Xxh3Builder::new(),
+                Xxh3Builder::new().with_seed(probe_seed1),
+                Xxh3Builder::new().with_seed(probe_seed2),
                 RingPosition::MAX,
             ),
+            seeds,
+        }
+    }
+}
+
+/// Serialized form of the partitioners.
+///
+/// The `kind` tag identifies the hash function, so that a ring is never
+/// restored with a partitioner other than the one it was built with.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum PartitionerRepr {
+    Xxh3 {
+        seed: RingPosition,
+        probe_seeds: [RingPosition; 2],
+    },
+    #[cfg(feature = "siphash")]
+    SipHash {
+        keys: [u64; 2],
+        seed: RingPosition,
+        probe_seeds: [RingPosition; 2],
+    },
+    #[cfg(feature = "murmur3")]
+    Murmur3 {
+        seed: RingPosition,
+        probe_seeds: [RingPosition; 2],
+    },
+    #[cfg(feature = "fnv")]
+    Fnv1a {
+        seed: RingPosition,
+        probe_seeds: [RingPosition; 2],
+    },
+    #[cfg(feature = "blake3")]
+    Blake3 {
+        seed: RingPosition,
+        probe_seeds: [RingPosition; 2],
+    },
+}
+
+#[cfg(feature = "serde")]
+impl PartitionerRepr {
+    /// Returns the error for the partitioner of unexpected kind.
+    pub(crate) fn mismatch(expected: &str) -> String {
+        format!("expected partitioner of kind `{expected}`")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PartitionerRepr> for Xxh3Partitioner {
+    type Error = String;
+
+    fn try_from(repr: PartitionerRepr) -> Result<Self, Self::Error> {
+        match repr {
+            PartitionerRepr::Xxh3 { seed, probe_seeds } => {
+                Ok(Self::from(Xxh3Seeds { seed, probe_seeds }))
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(PartitionerRepr::mismatch("xxh3")),
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl From<Xxh3Partitioner> for PartitionerRepr {
+    fn from(partitioner: Xxh3Partitioner) -> Self {
+        let Xxh3Seeds { seed, probe_seeds } = partitioner.seeds;
+        Self::Xxh3 { seed, probe_seeds }
+    }
+}
+
 impl Xxh3Partitioner {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Creates a partitioner with custom seeds.
+    ///
+    /// The first seed is used to compute the main position of a key (see
+    /// [`Partitioner::position`]), while both seeds are used to initialize the
+    /// hashers producing the probe sequence for double hashing (see
+    /// [`Partitioner::positions`]).
+    ///
+    /// The default partitioner produces probes with unseeded hashers, so
+    /// `with_seeds(DEFAULT_SEED1, DEFAULT_SEED2)` results in a ring different
+    /// from the default one. Use [`with_probe_seeds()`](Self::with_probe_seeds)
+    /// to reproduce the default partitioner.
+    pub fn with_seeds(seed1: RingPosition, seed2: RingPosition) -> Self {
+        Self::with_probe_seeds(seed1, [seed1, seed2])
+    }
+
+    /// Creates a partitioner with custom seeds, setting the seeds of the
+    /// probe sequence hashers separately from the main seed.
+    ///
+    /// The default partitioner is equivalent to
+    /// `with_probe_seeds(DEFAULT_SEED1, DEFAULT_PROBE_SEEDS)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{Partitioner, Xxh3Partitioner, DEFAULT_PROBE_SEEDS, DEFAULT_SEED1};
+    ///
+    /// let partitioner = Xxh3Partitioner::with_probe_seeds(DEFAULT_SEED1, DEFAULT_PROBE_SEEDS);
+    /// let default = Xxh3Partitioner::new();
+    /// assert_eq!(
+    ///     partitioner.positions(&"key", 5).collect::<Vec<_>>(),
+    ///     default.positions(&"key", 5).collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn with_probe_seeds(seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        Self::from(Xxh3Seeds { seed, probe_seeds })
+    }
+
     pub fn hash<K: Hash>(&self, key: &K, seed: RingPosition) -> RingPosition {
         self.hash_builder.with_seed(seed).hash_one(key)
     }
 }
 
+impl WirePartitioner for Xxh3Partitioner {
+    const ID: u8 = 1;
+
+    fn encode_params(&self, buf: &mut Vec<u8>) {
+        let Xxh3Seeds { seed, probe_seeds } = self.seeds;
+        for seed in [seed, probe_seeds[0], probe_seeds[1]] {
+            buf.extend_from_slice(&seed.to_le_bytes());
+        }
+    }
+
+    fn decode_params(bytes: &[u8]) -> Option<Self> {
+        let seeds: &[u8; 24] = bytes.try_into().ok()?;
+        let seed = |idx: usize| {
+            RingPosition::from_le_bytes(seeds[idx * 8..][..8].try_into().expect("seed size"))
+        };
+        Some(Self::from(Xxh3Seeds {
+            seed: seed(0),
+            probe_seeds: [seed(1), seed(2)],
+        }))
+    }
+}
+
 impl<K: Hash> Partitioner<K> for Xxh3Partitioner {
     fn position(&self, key: &K) -> RingPosition {
-        self.hash(key, DEFAULT_SEED1)
+        self.hash(key, self.seeds.seed)
     }
 
     fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
@@ -78,3 +255,28 @@ impl<K: Hash> Partitioner<K> for Xxh3Partitioner {
 
 /// Default partitioner.
 pub type DefaultPartitioner = Xxh3Partitioner;
+
+/// Key hashed as raw bytes.
+///
+/// Standard `Hash` implementations of strings and slices feed extra data into
+/// the hasher (e.g. a terminator or a length prefix), so their positions differ
+/// from the hash of their bytes. Wrap the key to hash its bytes only, e.g. to
+/// match hashes computed by other systems using the same hash function.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{Partitioner, RawKey, Xxh3Partitioner};
+///
+/// let partitioner = Xxh3Partitioner::new();
+/// let hash = xxhash_rust::xxh3::xxh3_64_with_seed(b"key", mpchash::DEFAULT_SEED1);
+/// assert_eq!(partitioner.position(&RawKey("key")), hash);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawKey<T>(pub T);
+
+impl<T: AsRef<[u8]>> Hash for RawKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write(self.0.as_ref());
+    }
+}
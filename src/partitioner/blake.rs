@@ -0,0 +1,137 @@
+use {
+    super::seeded::{decode_seeds, SeedableHasher, SeededPartitioner},
+    crate::{Partitioner, RingPosition, WirePartitioner, DEFAULT_SEED1, DEFAULT_SEED2},
+    hash_iter::HashIterHasher,
+    std::hash::{BuildHasher, Hash, Hasher},
+};
+
+/// A partitioner that uses a BLAKE3 hash function to partition data.
+///
+/// Key position is the first 8 bytes (little-endian) of the hash. With the
+/// default (zero) seed, positions match the plain BLAKE3 hash of the key bytes,
+/// provided the key is wrapped into [`RawKey`](crate::RawKey). Non-zero seeds
+/// switch to the keyed mode, with the seed (little-endian, zero-padded) as the
+/// key.
+///
+/// BLAKE3 is a cryptographic hash function, and is slower than the
+/// alternatives. Note that seeds are not secret (they are part of the
+/// serialized ring state, and the default one is zero), so positions are
+/// still predictable: use [`KeyedPartitioner`](crate::KeyedPartitioner) if
+/// keys are controlled by an attacker.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "super::PartitionerRepr", into = "super::PartitionerRepr")
+)]
+pub struct Blake3Partitioner(SeededPartitioner<Blake3Builder>);
+
+/// Builds BLAKE3 hashers with a given seed.
+#[derive(Clone, Copy)]
+struct Blake3Builder(RingPosition);
+
+/// Adapts BLAKE3 hasher to the [`Hasher`] interface.
+struct Blake3Hasher(blake3::Hasher);
+
+impl Hasher for Blake3Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let hash = self.0.finalize();
+        u64::from_le_bytes(hash.as_bytes()[..8].try_into().expect("hash size"))
+    }
+}
+
+impl BuildHasher for Blake3Builder {
+    type Hasher = Blake3Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        if self.0 == 0 {
+            return Blake3Hasher(blake3::Hasher::new());
+        }
+        let mut key = [0; blake3::KEY_LEN];
+        key[..8].copy_from_slice(&self.0.to_le_bytes());
+        Blake3Hasher(blake3::Hasher::new_keyed(&key))
+    }
+}
+
+impl SeedableHasher for Blake3Builder {
+    fn with_seed(&self, seed: RingPosition) -> Self {
+        Self(seed)
+    }
+}
+
+impl Default for Blake3Partitioner {
+    fn default() -> Self {
+        Self::from_seeds(0, [DEFAULT_SEED1, DEFAULT_SEED2])
+    }
+}
+
+impl Blake3Partitioner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a partitioner with custom seeds.
+    ///
+    /// See [`Xxh3Partitioner::with_seeds`](crate::Xxh3Partitioner::with_seeds)
+    /// for details.
+    pub fn with_seeds(seed1: RingPosition, seed2: RingPosition) -> Self {
+        Self::from_seeds(seed1, [seed1, seed2])
+    }
+
+    fn from_seeds(seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        Self(SeededPartitioner::new(Blake3Builder(0), seed, probe_seeds))
+    }
+}
+
+impl WirePartitioner for Blake3Partitioner {
+    const ID: u8 = 5;
+
+    fn encode_params(&self, buf: &mut Vec<u8>) {
+        self.0.encode_seeds(buf);
+    }
+
+    fn decode_params(bytes: &[u8]) -> Option<Self> {
+        let (seed, probe_seeds) = decode_seeds(bytes)?;
+        Some(Self::from_seeds(seed, probe_seeds))
+    }
+}
+
+impl<K: Hash> Partitioner<K> for Blake3Partitioner {
+    fn position(&self, key: &K) -> RingPosition {
+        self.0.position(key)
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        self.0.hash_iter().hash_iter(key, k)
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.0.position_seeded(key, seed)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<super::PartitionerRepr> for Blake3Partitioner {
+    type Error = String;
+
+    fn try_from(repr: super::PartitionerRepr) -> Result<Self, Self::Error> {
+        match repr {
+            super::PartitionerRepr::Blake3 { seed, probe_seeds } => {
+                Ok(Self::from_seeds(seed, probe_seeds))
+            }
+            _ => Err(super::PartitionerRepr::mismatch("blake3")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Blake3Partitioner> for super::PartitionerRepr {
+    fn from(partitioner: Blake3Partitioner) -> Self {
+        let (seed, probe_seeds) = partitioner.0.seeds();
+        Self::Blake3 { seed, probe_seeds }
+    }
+}
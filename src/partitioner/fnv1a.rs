@@ -0,0 +1,122 @@
+use {
+    super::seeded::{decode_seeds, SeedableHasher, SeededPartitioner},
+    crate::{Partitioner, RingPosition, WirePartitioner, DEFAULT_SEED1, DEFAULT_SEED2},
+    fnv::FnvHasher,
+    hash_iter::HashIterHasher,
+    std::hash::{BuildHasher, Hash},
+};
+
+/// Offset basis of the 64-bit FNV-1a hash function.
+const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// A partitioner that uses a 64-bit FNV-1a hash function to partition data.
+///
+/// With the default (zero) seed, key positions match the standard FNV-1a hash
+/// of the key bytes (e.g. Go's `hash/fnv.New64a()`), provided the key is
+/// wrapped into [`RawKey`](crate::RawKey). Seeds are mixed into the offset
+/// basis.
+///
+/// FNV-1a is fast on short keys, but is not resistant to hash-flooding: use
+/// [`SipHashPartitioner`](crate::SipHashPartitioner) for user-controlled keys.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "super::PartitionerRepr", into = "super::PartitionerRepr")
+)]
+pub struct Fnv1aPartitioner(SeededPartitioner<FnvBuilder>);
+
+/// Builds FNV-1a hashers with a given offset basis.
+#[derive(Clone, Copy)]
+struct FnvBuilder(u64);
+
+impl BuildHasher for FnvBuilder {
+    type Hasher = FnvHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        FnvHasher::with_key(self.0)
+    }
+}
+
+impl SeedableHasher for FnvBuilder {
+    fn with_seed(&self, seed: RingPosition) -> Self {
+        Self(OFFSET_BASIS ^ seed)
+    }
+}
+
+impl Default for Fnv1aPartitioner {
+    fn default() -> Self {
+        Self::from_seeds(0, [DEFAULT_SEED1, DEFAULT_SEED2])
+    }
+}
+
+impl Fnv1aPartitioner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a partitioner with custom seeds.
+    ///
+    /// See [`Xxh3Partitioner::with_seeds`](crate::Xxh3Partitioner::with_seeds)
+    /// for details.
+    pub fn with_seeds(seed1: RingPosition, seed2: RingPosition) -> Self {
+        Self::from_seeds(seed1, [seed1, seed2])
+    }
+
+    fn from_seeds(seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        Self(SeededPartitioner::new(
+            FnvBuilder(OFFSET_BASIS),
+            seed,
+            probe_seeds,
+        ))
+    }
+}
+
+impl WirePartitioner for Fnv1aPartitioner {
+    const ID: u8 = 4;
+
+    fn encode_params(&self, buf: &mut Vec<u8>) {
+        self.0.encode_seeds(buf);
+    }
+
+    fn decode_params(bytes: &[u8]) -> Option<Self> {
+        let (seed, probe_seeds) = decode_seeds(bytes)?;
+        Some(Self::from_seeds(seed, probe_seeds))
+    }
+}
+
+impl<K: Hash> Partitioner<K> for Fnv1aPartitioner {
+    fn position(&self, key: &K) -> RingPosition {
+        self.0.position(key)
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        self.0.hash_iter().hash_iter(key, k)
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.0.position_seeded(key, seed)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<super::PartitionerRepr> for Fnv1aPartitioner {
+    type Error = String;
+
+    fn try_from(repr: super::PartitionerRepr) -> Result<Self, Self::Error> {
+        match repr {
+            super::PartitionerRepr::Fnv1a { seed, probe_seeds } => {
+                Ok(Self::from_seeds(seed, probe_seeds))
+            }
+            _ => Err(super::PartitionerRepr::mismatch("fnv1a")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Fnv1aPartitioner> for super::PartitionerRepr {
+    fn from(partitioner: Fnv1aPartitioner) -> Self {
+        let (seed, probe_seeds) = partitioner.0.seeds();
+        Self::Fnv1a { seed, probe_seeds }
+    }
+}
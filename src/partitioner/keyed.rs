@@ -0,0 +1,156 @@
+use {
+    super::seeded::{SeededPartitioner, SipBuilder},
+    crate::{Partitioner, RingPosition, DEFAULT_SEED1, DEFAULT_SEED2},
+    hash_iter::HashIterHasher,
+    siphasher::sip::SipHasher13,
+    std::{fmt, hash::Hash},
+};
+
+/// Secret 128-bit key of a [`KeyedPartitioner`].
+///
+/// The key is never printed: its `Debug` implementation is redacted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SecretKey([u8; 16]);
+
+impl SecretKey {
+    /// Generates a random key.
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// Creates a key from its bytes.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the bytes of the key.
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Returns the key as two 64-bit halves (little-endian).
+    fn halves(&self) -> [u64; 2] {
+        let (key0, key1) = self.0.split_at(8);
+        [
+            u64::from_le_bytes(key0.try_into().expect("key size")),
+            u64::from_le_bytes(key1.try_into().expect("key size")),
+        ]
+    }
+}
+
+impl From<[u8; 16]> for SecretKey {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<u128> for SecretKey {
+    fn from(key: u128) -> Self {
+        Self::from_bytes(key.to_le_bytes())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+/// A partitioner that uses a secret key to partition data.
+///
+/// Positions are computed with SipHash-1-3 keyed by a 128-bit secret, so they
+/// cannot be predicted without knowing the secret. This prevents an attacker,
+/// who controls the keys, from crafting keys which all land on the same node
+/// (hash-flooding), which is trivial with the public seeds of
+/// [`Xxh3Partitioner`](crate::Xxh3Partitioner).
+///
+/// Positions (of both keys and nodes) are reproducible: processes sharing the
+/// secret compute the same ring. By default, a random secret is generated, so
+/// use [`with_secret()`](Self::with_secret) to share the ring across processes.
+///
+/// The partitioner is deliberately neither serializable nor encodable (see
+/// [`HashRing::encode`](crate::HashRing::encode)), so that the secret is never
+/// persisted or sent over the wire by accident. Use
+/// [`HashRing::to_state`](crate::HashRing::to_state) to persist the ring, and
+/// supply the partitioner when restoring it.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{HashRingBuilder, KeyedPartitioner, SecretKey};
+///
+/// let secret = SecretKey::from(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+/// let ring1 = HashRingBuilder::new()
+///     .partitioner(KeyedPartitioner::with_secret(secret))
+///     .build::<u64>();
+/// let ring2 = HashRingBuilder::new()
+///     .partitioner(KeyedPartitioner::with_secret(secret))
+///     .build::<u64>();
+/// for i in 0..10 {
+///     ring1.add(i);
+///     ring2.add(i);
+/// }
+///
+/// // Rings sharing the secret route keys identically.
+/// for key in 0..100u64 {
+///     assert_eq!(
+///         ring1.node(&key).map(|t| *t.node()),
+///         ring2.node(&key).map(|t| *t.node())
+///     );
+/// }
+/// ```
+#[derive(Clone)]
+pub struct KeyedPartitioner {
+    secret: SecretKey,
+    inner: SeededPartitioner<SipBuilder<SipHasher13>>,
+}
+
+impl Default for KeyedPartitioner {
+    fn default() -> Self {
+        Self::with_secret(SecretKey::random())
+    }
+}
+
+impl KeyedPartitioner {
+    /// Creates a partitioner with a random secret.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a partitioner with a given secret.
+    pub fn with_secret(secret: SecretKey) -> Self {
+        let builder = SipBuilder::new(secret.halves());
+        Self {
+            secret,
+            inner: SeededPartitioner::new(builder, DEFAULT_SEED1, [DEFAULT_SEED1, DEFAULT_SEED2]),
+        }
+    }
+
+    /// Returns the secret of the partitioner.
+    pub fn secret(&self) -> &SecretKey {
+        &self.secret
+    }
+}
+
+impl fmt::Debug for KeyedPartitioner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedPartitioner")
+            .field("secret", &self.secret)
+            .field("seeds", &self.inner.seeds())
+            .finish()
+    }
+}
+
+impl<K: Hash> Partitioner<K> for KeyedPartitioner {
+    fn position(&self, key: &K) -> RingPosition {
+        self.inner.position(key)
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        self.inner.hash_iter().hash_iter(key, k)
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.inner.position_seeded(key, seed)
+    }
+}
@@ -0,0 +1,142 @@
+use {
+    super::seeded::{decode_seeds, SeedableHasher, SeededPartitioner},
+    crate::{Partitioner, RingPosition, WirePartitioner, DEFAULT_SEED1, DEFAULT_SEED2},
+    hash_iter::HashIterHasher,
+    murmur3::murmur3_x64_128,
+    std::hash::{BuildHasher, Hash, Hasher},
+};
+
+/// A partitioner that uses a MurmurHash3 (x64, 128-bit) hash function to
+/// partition data.
+///
+/// Key position is the lower half of the 128-bit hash, i.e. the first 64-bit
+/// word. With the default (zero) seed, positions match the reference
+/// MurmurHash3 of the key bytes, provided the key is wrapped into
+/// [`RawKey`](crate::RawKey).
+///
+/// Note that positions are not compatible with Cassandra's
+/// `Murmur3Partitioner` tokens: its implementation sign-extends the trailing
+/// bytes of the key, so keys having a byte above `0x7f` in the tail hash
+/// differently.
+///
+/// MurmurHash3 takes 32-bit seeds, so the upper and lower halves of 64-bit
+/// seeds are folded together.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "super::PartitionerRepr", into = "super::PartitionerRepr")
+)]
+pub struct Murmur3Partitioner(SeededPartitioner<MurmurBuilder>);
+
+/// Builds MurmurHash3 hashers with a given seed.
+#[derive(Clone, Copy)]
+struct MurmurBuilder(u32);
+
+/// MurmurHash3 is not a streaming hash, so the input is buffered.
+struct MurmurHasher {
+    seed: u32,
+    buf: Vec<u8>,
+}
+
+impl Hasher for MurmurHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let hash = murmur3_x64_128(&mut self.buf.as_slice(), self.seed)
+            .expect("reading from slice never fails");
+        hash as u64
+    }
+}
+
+impl BuildHasher for MurmurBuilder {
+    type Hasher = MurmurHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        MurmurHasher {
+            seed: self.0,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl SeedableHasher for MurmurBuilder {
+    fn with_seed(&self, seed: RingPosition) -> Self {
+        Self((seed ^ (seed >> 32)) as u32)
+    }
+}
+
+impl Default for Murmur3Partitioner {
+    fn default() -> Self {
+        Self::from_seeds(0, [DEFAULT_SEED1, DEFAULT_SEED2])
+    }
+}
+
+impl Murmur3Partitioner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a partitioner with custom seeds.
+    ///
+    /// See [`Xxh3Partitioner::with_seeds`](crate::Xxh3Partitioner::with_seeds)
+    /// for details.
+    pub fn with_seeds(seed1: RingPosition, seed2: RingPosition) -> Self {
+        Self::from_seeds(seed1, [seed1, seed2])
+    }
+
+    fn from_seeds(seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        Self(SeededPartitioner::new(MurmurBuilder(0), seed, probe_seeds))
+    }
+}
+
+impl WirePartitioner for Murmur3Partitioner {
+    const ID: u8 = 3;
+
+    fn encode_params(&self, buf: &mut Vec<u8>) {
+        self.0.encode_seeds(buf);
+    }
+
+    fn decode_params(bytes: &[u8]) -> Option<Self> {
+        let (seed, probe_seeds) = decode_seeds(bytes)?;
+        Some(Self::from_seeds(seed, probe_seeds))
+    }
+}
+
+impl<K: Hash> Partitioner<K> for Murmur3Partitioner {
+    fn position(&self, key: &K) -> RingPosition {
+        self.0.position(key)
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        self.0.hash_iter().hash_iter(key, k)
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.0.position_seeded(key, seed)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<super::PartitionerRepr> for Murmur3Partitioner {
+    type Error = String;
+
+    fn try_from(repr: super::PartitionerRepr) -> Result<Self, Self::Error> {
+        match repr {
+            super::PartitionerRepr::Murmur3 { seed, probe_seeds } => {
+                Ok(Self::from_seeds(seed, probe_seeds))
+            }
+            _ => Err(super::PartitionerRepr::mismatch("murmur3")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Murmur3Partitioner> for super::PartitionerRepr {
+    fn from(partitioner: Murmur3Partitioner) -> Self {
+        let (seed, probe_seeds) = partitioner.0.seeds();
+        Self::Murmur3 { seed, probe_seeds }
+    }
+}
@@ -0,0 +1,156 @@
+use {
+    crate::RingPosition,
+    hash_iter::DoubleHashHasher,
+    siphasher::sip::{SipHasher13, SipHasher24},
+    std::{
+        hash::{BuildHasher, Hash, Hasher},
+        marker::PhantomData,
+    },
+};
+
+/// Hash function, which can be seeded.
+pub(super) trait SeedableHasher: BuildHasher + Clone {
+    /// Returns the hasher builder using the given seed.
+    fn with_seed(&self, seed: RingPosition) -> Self;
+}
+
+/// Variant of the SipHash function, keyed by a 128-bit key.
+pub(super) trait SipHasher: Hasher {
+    /// Creates a hasher with the given key (as two 64-bit halves).
+    fn with_keys(key0: u64, key1: u64) -> Self;
+}
+
+impl SipHasher for SipHasher13 {
+    fn with_keys(key0: u64, key1: u64) -> Self {
+        Self::new_with_keys(key0, key1)
+    }
+}
+
+impl SipHasher for SipHasher24 {
+    fn with_keys(key0: u64, key1: u64) -> Self {
+        Self::new_with_keys(key0, key1)
+    }
+}
+
+/// Builds SipHash hashers keyed by a 128-bit key mixed with a seed.
+///
+/// Seeds are mixed into the first half of the key.
+pub(super) struct SipBuilder<H> {
+    /// The key of the partitioner.
+    keys: [u64; 2],
+
+    /// The key used by hashers, i.e. the partitioner key mixed with the seed.
+    seeded: [u64; 2],
+
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<H> SipBuilder<H> {
+    pub(super) const fn new(keys: [u64; 2]) -> Self {
+        Self {
+            keys,
+            seeded: keys,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H> Clone for SipBuilder<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for SipBuilder<H> {}
+
+impl<H: SipHasher> BuildHasher for SipBuilder<H> {
+    type Hasher = H;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        H::with_keys(self.seeded[0], self.seeded[1])
+    }
+}
+
+impl<H: SipHasher> SeedableHasher for SipBuilder<H> {
+    fn with_seed(&self, seed: RingPosition) -> Self {
+        let [key0, key1] = self.keys;
+        Self {
+            seeded: [key0 ^ seed, key1],
+            ..*self
+        }
+    }
+}
+
+/// Partitioner built on top of a seedable hash function.
+///
+/// Key position is computed with the main seed, while the probe sequence is
+/// produced by double hashing, with hashers using two probe seeds.
+#[derive(Clone)]
+pub(super) struct SeededPartitioner<B> {
+    build_hasher: B,
+    seed: RingPosition,
+    probe_seeds: [RingPosition; 2],
+    hash_iter: DoubleHashHasher<RingPosition, B, B>,
+}
+
+impl<B: SeedableHasher> SeededPartitioner<B> {
+    pub(super) fn new(build_hasher: B, seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        let hash_iter = DoubleHashHasher::with_hash_builders(
+            build_hasher.with_seed(probe_seeds[0]),
+            build_hasher.with_seed(probe_seeds[1]),
+            RingPosition::MAX,
+        );
+        Self {
+            build_hasher,
+            seed,
+            probe_seeds,
+            hash_iter,
+        }
+    }
+
+    pub(super) fn seeds(&self) -> (RingPosition, [RingPosition; 2]) {
+        (self.seed, self.probe_seeds)
+    }
+
+    pub(super) fn position<K: Hash>(&self, key: &K) -> RingPosition {
+        self.position_seeded(key, self.seed)
+    }
+
+    /// Returns the hasher producing the probe sequence.
+    pub(super) fn hash_iter(&self) -> &DoubleHashHasher<RingPosition, B, B> {
+        &self.hash_iter
+    }
+
+    pub(super) fn position_seeded<K: Hash>(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.build_hasher.with_seed(seed).hash_one(key)
+    }
+
+    /// Appends the seeds to the buffer.
+    #[cfg(any(
+        feature = "siphash",
+        feature = "murmur3",
+        feature = "fnv",
+        feature = "blake3"
+    ))]
+    pub(super) fn encode_seeds(&self, buf: &mut Vec<u8>) {
+        let (seed, [probe_seed1, probe_seed2]) = self.seeds();
+        for seed in [seed, probe_seed1, probe_seed2] {
+            buf.extend_from_slice(&seed.to_le_bytes());
+        }
+    }
+}
+
+/// Decodes the seeds encoded with [`SeededPartitioner::encode_seeds`].
+#[cfg(any(
+    feature = "siphash",
+    feature = "murmur3",
+    feature = "fnv",
+    feature = "blake3"
+))]
+pub(super) fn decode_seeds(bytes: &[u8]) -> Option<(RingPosition, [RingPosition; 2])> {
+    let seeds: &[u8; 24] = bytes.try_into().ok()?;
+    let seed = |idx: usize| {
+        RingPosition::from_le_bytes(seeds[idx * 8..][..8].try_into().expect("seed size"))
+    };
+    Some((seed(0), [seed(1), seed(2)]))
+}
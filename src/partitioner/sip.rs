@@ -0,0 +1,144 @@
+use {
+    super::seeded::{decode_seeds, SeededPartitioner, SipBuilder},
+    crate::{Partitioner, RingPosition, WirePartitioner, DEFAULT_SEED1, DEFAULT_SEED2},
+    hash_iter::HashIterHasher,
+    siphasher::sip::SipHasher24,
+    std::hash::Hash,
+};
+
+/// A partitioner that uses a SipHash-2-4 hash function to partition data.
+///
+/// SipHash is a keyed hash function: without knowing the 128-bit key, an
+/// attacker cannot craft keys landing on the same node (hash-flooding). So,
+/// the resistance holds only as long as the key is secret: by default, a
+/// random key is generated, while [`with_keys()`](Self::with_keys) is meant
+/// for a secret shared across processes. Note that the key is part of the
+/// partitioner state, i.e. serializing the partitioner (or the ring using
+/// it), or encoding the ring with [`HashRing::encode`](crate::HashRing::encode)
+/// exposes the key. Use [`KeyedPartitioner`](crate::KeyedPartitioner) if the
+/// secret must never leave the process.
+///
+/// With the default (zero) seed, key positions match the SipHash-2-4 of the
+/// key bytes under the partitioner key, provided the key is wrapped into
+/// [`RawKey`](crate::RawKey). Seeds are mixed into the first half of the key.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "super::PartitionerRepr", into = "super::PartitionerRepr")
+)]
+pub struct SipHashPartitioner {
+    keys: [u64; 2],
+    inner: SeededPartitioner<SipBuilder<SipHasher24>>,
+}
+
+impl Default for SipHashPartitioner {
+    fn default() -> Self {
+        let [key0, key1] = rand::random();
+        Self::with_keys(key0, key1)
+    }
+}
+
+impl SipHashPartitioner {
+    /// Creates a partitioner with a random key.
+    ///
+    /// Rings using different partitioners created this way place nodes and
+    /// keys differently: use [`with_keys()`](Self::with_keys) to share the
+    /// ring across processes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a partitioner with a given 128-bit key (as two 64-bit halves).
+    pub fn with_keys(key0: u64, key1: u64) -> Self {
+        Self::from_parts([key0, key1], 0, [DEFAULT_SEED1, DEFAULT_SEED2])
+    }
+
+    /// Creates a partitioner with custom seeds (and zero key).
+    ///
+    /// The key is publicly known, so the partitioner is not resistant to
+    /// hash-flooding. See
+    /// [`Xxh3Partitioner::with_seeds`](crate::Xxh3Partitioner::with_seeds)
+    /// for details.
+    pub fn with_seeds(seed1: RingPosition, seed2: RingPosition) -> Self {
+        Self::from_parts([0, 0], seed1, [seed1, seed2])
+    }
+
+    /// Returns the key of the partitioner.
+    pub fn keys(&self) -> (u64, u64) {
+        let [key0, key1] = self.keys;
+        (key0, key1)
+    }
+
+    fn from_parts(keys: [u64; 2], seed: RingPosition, probe_seeds: [RingPosition; 2]) -> Self {
+        Self {
+            keys,
+            inner: SeededPartitioner::new(SipBuilder::new(keys), seed, probe_seeds),
+        }
+    }
+}
+
+impl WirePartitioner for SipHashPartitioner {
+    const ID: u8 = 2;
+
+    fn encode_params(&self, buf: &mut Vec<u8>) {
+        let (key0, key1) = self.keys();
+        buf.extend_from_slice(&key0.to_le_bytes());
+        buf.extend_from_slice(&key1.to_le_bytes());
+        self.inner.encode_seeds(buf);
+    }
+
+    fn decode_params(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 16 {
+            return None;
+        }
+        let (keys, seeds) = bytes.split_at(16);
+        let key =
+            |idx: usize| u64::from_le_bytes(keys[idx * 8..][..8].try_into().expect("key size"));
+        let (seed, probe_seeds) = decode_seeds(seeds)?;
+        Some(Self::from_parts([key(0), key(1)], seed, probe_seeds))
+    }
+}
+
+impl<K: Hash> Partitioner<K> for SipHashPartitioner {
+    fn position(&self, key: &K) -> RingPosition {
+        self.inner.position(key)
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        self.inner.hash_iter().hash_iter(key, k)
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        self.inner.position_seeded(key, seed)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<super::PartitionerRepr> for SipHashPartitioner {
+    type Error = String;
+
+    fn try_from(repr: super::PartitionerRepr) -> Result<Self, Self::Error> {
+        match repr {
+            super::PartitionerRepr::SipHash {
+                keys,
+                seed,
+                probe_seeds,
+            } => Ok(Self::from_parts(keys, seed, probe_seeds)),
+            _ => Err(super::PartitionerRepr::mismatch("siphash")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<SipHashPartitioner> for super::PartitionerRepr {
+    fn from(partitioner: SipHashPartitioner) -> Self {
+        let (key0, key1) = partitioner.keys();
+        let (seed, probe_seeds) = partitioner.inner.seeds();
+        Self::SipHash {
+            keys: [key0, key1],
+            seed,
+            probe_seeds,
+        }
+    }
+}
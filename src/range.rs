@@ -13,6 +13,7 @@ use {
 /// If `start >= end`, the range is considered wrapping and is equivalent to
 /// covering union of two ranges: `[start..MAX_VALUE]` and `[0..end)`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyRange<Idx>
 where
     Idx: Bounded,
@@ -0,0 +1,296 @@
+use {
+    crate::{
+        HashRing,
+        KeyRange,
+        Partitioner,
+        RingDirection::Clockwise,
+        RingNode,
+        RingPosition,
+        RingToken,
+        DEFAULT_WEIGHT,
+    },
+    std::{
+        collections::HashMap,
+        hash::{BuildHasher, RandomState},
+    },
+};
+
+/// A single data transfer between two nodes.
+///
+/// Keys which are currently owned (as primary) by `from`, and whose winning
+/// probe position on the target ring (see [`HashRing::probe_position`]) falls
+/// into `range`, must be copied from `from` to `to`.
+///
+/// Note that the range is expressed in terms of probe positions (and not
+/// plain key positions), see [`HashRing::intervals`] for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer<N> {
+    pub range: KeyRange<RingPosition>,
+    pub from: N,
+    pub to: N,
+}
+
+/// Data transfers required to move from the current ring to the target one.
+///
+/// Plan is computed without modifying the live ring: the target ring is a
+/// detached copy of the live ring with the membership change applied. Once
+/// all the transfers are executed, the change can be committed to the live
+/// ring.
+pub struct RebalancePlan<N: RingNode, P> {
+    /// The ring after the membership change.
+    target: HashRing<N, P>,
+
+    /// Required data transfers.
+    transfers: Vec<Transfer<N>>,
+}
+
+impl<N: RingNode, P> RebalancePlan<N, P> {
+    /// Returns the ring state after the membership change.
+    ///
+    /// Use it to find winning probe positions of keys on the target ring.
+    pub fn target(&self) -> &HashRing<N, P> {
+        &self.target
+    }
+
+    /// Returns the data transfers.
+    pub fn transfers(&self) -> &[Transfer<N>] {
+        &self.transfers
+    }
+
+    /// Returns `true` if no data needs to be moved.
+    pub fn is_empty(&self) -> bool {
+        self.transfers.is_empty()
+    }
+}
+
+impl<N, P> HashRing<N, P>
+where
+    N: RingNode + Clone,
+    P: Partitioner<N> + Clone,
+{
+    /// Returns data transfers required to add the given node to the ring.
+    ///
+    /// Each key is expected to be stored on `replication_factor` nodes (see
+    /// [`replicas()`](Self::replicas)), so transfers include both primary and
+    /// replica moves.
+    ///
+    /// Under multi-probe hashing, the new node captures keys from all the
+    /// nodes (not only from its clockwise neighbour), so transfers to the new
+    /// node are listed for every existing node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.add(1);
+    /// ring.add(2);
+    ///
+    /// let plan = ring.plan_add(&3, 1);
+    /// assert!(plan.transfers().iter().all(|t| t.to == 3));
+    ///
+    /// // The live ring is not modified.
+    /// assert_eq!(ring.len(), 2);
+    /// assert_eq!(plan.target().len(), 3);
+    /// ```
+    pub fn plan_add(&self, node: &N, replication_factor: usize) -> RebalancePlan<N, P> {
+        self.plan_add_weighted(node, DEFAULT_WEIGHT, replication_factor)
+    }
+
+    /// Returns data transfers required to add the given node with a given
+    /// weight to the ring.
+    ///
+    /// See [`plan_add()`](Self::plan_add) and
+    /// [`add_weighted()`](Self::add_weighted) for details.
+    pub fn plan_add_weighted(
+        &self,
+        node: &N,
+        weight: u32,
+        replication_factor: usize,
+    ) -> RebalancePlan<N, P> {
+        let target = self.duplicate();
+        let all_nodes = self.nodes_cloned();
+        if self.weight(node) == Some(weight) {
+            return self.plan(target, replication_factor, |owner| vec![owner.clone()]);
+        }
+        if self.position_of(node).is_some() {
+            // Only the weight of the node changes: the node may either capture keys
+            // from any node, or give its keys away to any node.
+            target.add_weighted(node.clone(), weight);
+            return self.plan(target, replication_factor, |owner| {
+                if owner == node {
+                    all_nodes.clone()
+                } else {
+                    vec![owner.clone(), node.clone()]
+                }
+            });
+        }
+        let Ok(pos) = target.try_add_weighted(node.clone(), weight) else {
+            return self.plan(target, replication_factor, |owner| vec![owner.clone()]);
+        };
+
+        // Keys captured by the new node may come from any node. Additionally, if
+        // the new node is lighter than its clockwise neighbour, some keys of the
+        // neighbour are now better off elsewhere.
+        let neighbour = self
+            .owner(pos)
+            .filter(|token| self.weight_at(token.position()) > weight)
+            .map(|token| token.node().clone());
+        self.plan(target, replication_factor, |owner| {
+            if owner == node {
+                return all_nodes.clone();
+            }
+            let mut sources = vec![owner.clone()];
+            sources.extend(neighbour.clone().filter(|neighbour| neighbour != owner));
+            sources
+        })
+    }
+
+    /// Returns data transfers required to remove the given node from the
+    /// ring.
+    ///
+    /// Keys owned by the removed node are spread over the remaining nodes, so
+    /// transfers from the removed node are listed for every remaining node.
+    /// The node is removed from all of its positions, including the ones
+    /// assigned with [`insert()`](Self::insert) (use
+    /// [`remove_at()`](Self::remove_at) to commit such a change). See
+    /// [`plan_add()`](Self::plan_add) for details.
+    pub fn plan_remove(&self, node: &N, replication_factor: usize) -> RebalancePlan<N, P> {
+        let target = self.duplicate();
+        let positions = self
+            .tokens_of(node)
+            .map(|token| token.position())
+            .collect::<Vec<_>>();
+        if positions.is_empty() {
+            return self.plan(target, replication_factor, |owner| vec![owner.clone()]);
+        }
+        for pos in &positions {
+            target.remove_at(*pos);
+        }
+
+        // Keys of the removed node may go to any node. Additionally, if the
+        // clockwise neighbour of any removed position is heavier than the
+        // removed node there, it may capture keys from all the nodes.
+        let all_nodes = self.nodes_cloned();
+        let neighbours = positions
+            .iter()
+            .filter_map(|pos| {
+                let weight = self.weight_at(*pos);
+                self.tokens(*pos, Clockwise)
+                    .find(|token| token.node() != node)
+                    .filter(|token| self.weight_at(token.position()) > weight)
+                    .map(|token| token.node().clone())
+            })
+            .collect::<Vec<_>>();
+        self.plan(target, replication_factor, |owner| {
+            if neighbours.contains(owner) {
+                return all_nodes.clone();
+            }
+            vec![owner.clone(), node.clone()]
+        })
+    }
+
+    /// Returns distinct (cloned) nodes of the ring.
+    fn nodes_cloned(&self) -> Vec<N> {
+        NodeTokens::new(self).nodes
+    }
+
+    /// Computes transfers from the current ring to the target one.
+    ///
+    /// For each interval of the target ring, `sources` returns all the
+    /// (distinct) nodes, given the interval owner on the target ring, which
+    /// may be primary owners (on the current ring) of keys captured by that
+    /// interval. Then every node, which is a replica for the interval on the
+    /// target ring but not a replica for the source, must receive data from
+    /// the source.
+    ///
+    /// Listing extra sources is harmless (they will have no matching keys),
+    /// but missing ones results in lost data.
+    fn plan<F>(
+        &self,
+        target: HashRing<N, P>,
+        replication_factor: usize,
+        sources: F,
+    ) -> RebalancePlan<N, P>
+    where
+        F: Fn(&N) -> Vec<N>,
+    {
+        let tokens = NodeTokens::new(self);
+        let mut transfers = Vec::new();
+        for (range, owner) in target.ownership() {
+            let new_replicas = target
+                .tokens(owner.position(), Clockwise)
+                .take(replication_factor)
+                .collect::<Vec<_>>();
+            for from in sources(&owner) {
+                // A node located at several positions may own keys through any
+                // of them, and replicas of such keys depend on the position.
+                let old_replicas = tokens
+                    .get(&from)
+                    .map(|token| {
+                        self.tokens(token.position(), Clockwise)
+                            .take(replication_factor)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let listed = transfers.len();
+                for to in &new_replicas {
+                    let missing = old_replicas
+                        .iter()
+                        .any(|replicas| replicas.iter().all(|token| token.node() != to.node()));
+                    let duplicate = transfers[listed..]
+                        .iter()
+                        .any(|t: &Transfer<N>| t.to == *to.node());
+                    if missing && !duplicate {
+                        transfers.push(Transfer {
+                            range: range.clone(),
+                            from: from.clone(),
+                            to: to.node().clone(),
+                        });
+                    }
+                }
+            }
+        }
+        RebalancePlan { target, transfers }
+    }
+}
+
+/// Tokens of a ring, grouped by node.
+///
+/// Nodes are only required to be `Hash + PartialEq`, so tokens are bucketed by
+/// the hash of the node, and compared for equality within a bucket.
+struct NodeTokens<'a, N> {
+    hasher: RandomState,
+    buckets: HashMap<u64, Vec<RingToken<'a, N>>>,
+
+    /// Distinct nodes, in the order of their first positions.
+    nodes: Vec<N>,
+}
+
+impl<'a, N: RingNode + Clone> NodeTokens<'a, N> {
+    fn new<P: Partitioner<N>>(ring: &'a HashRing<N, P>) -> Self {
+        let hasher = RandomState::new();
+        let mut buckets = HashMap::<_, Vec<RingToken<'a, N>>>::new();
+        let mut nodes = Vec::new();
+        for token in ring.nodes() {
+            let bucket = buckets.entry(hasher.hash_one(token.node())).or_default();
+            if bucket.iter().all(|other| other.node() != token.node()) {
+                nodes.push(token.node().clone());
+            }
+            bucket.push(token);
+        }
+        Self {
+            hasher,
+            buckets,
+            nodes,
+        }
+    }
+
+    /// Returns tokens of all the positions of the given node.
+    fn get<'b>(&'b self, node: &'b N) -> impl Iterator<Item = &'b RingToken<'a, N>> + 'b {
+        self.buckets
+            .get(&self.hasher.hash_one(node))
+            .into_iter()
+            .flatten()
+            .filter(move |token| token.node() == node)
+    }
+}
@@ -0,0 +1,273 @@
+use {
+    crate::{
+        DefaultPartitioner,
+        FailoverToken,
+        HashRing,
+        KeyRange,
+        NodeHealth,
+        OwnedRingToken,
+        Partitioner,
+        RingDiff,
+        RingDirection,
+        RingNode,
+        RingPosition,
+        RingToken,
+        ZonedNode,
+    },
+    std::hash::Hash,
+};
+
+/// Immutable snapshot of a [`HashRing`].
+///
+/// Snapshot is a consistent view of the ring at some epoch (see
+/// [`HashRing::epoch`]): modifications of the ring made after the snapshot
+/// was taken are not visible. Snapshots support all the read operations of
+/// the ring, and are cheap to clone (but not to take, see
+/// [`HashRing::snapshot`]).
+///
+/// # Examples
+///
+/// ```
+/// let ring = mpchash::HashRing::<u64>::new();
+/// (0..5).for_each(|i| ring.add(i));
+///
+/// // Route the whole operation against the same ring state.
+/// let snapshot = ring.snapshot();
+/// let primary = *snapshot.node(&"key").expect("empty ring");
+/// ring.remove(&primary);
+/// let replicas = snapshot.replicas(&"key", 3);
+/// assert_eq!(replicas[0], primary);
+/// ```
+#[derive(Clone)]
+pub struct RingSnapshot<N: RingNode, P = DefaultPartitioner> {
+    /// Detached copy of the ring, which is never modified.
+    ring: HashRing<N, P>,
+
+    /// The epoch at which the snapshot was taken.
+    epoch: u64,
+}
+
+impl<N: RingNode, P: Partitioner<N>> RingSnapshot<N, P> {
+    /// Creates a snapshot from a detached ring.
+    pub(crate) fn new(ring: HashRing<N, P>) -> Self {
+        let epoch = ring.epoch();
+        Self { ring, epoch }
+    }
+
+    /// Returns the epoch at which the snapshot was taken.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// See [`HashRing::probe_count`].
+    pub fn probe_count(&self) -> usize {
+        self.ring.probe_count()
+    }
+
+    /// See [`HashRing::partitioner`].
+    pub fn partitioner(&self) -> &P {
+        self.ring.partitioner()
+    }
+
+    /// See [`HashRing::weight`].
+    pub fn weight(&self, node: &N) -> Option<u32> {
+        self.ring.weight(node)
+    }
+
+    /// See [`HashRing::replicas`].
+    pub fn replicas<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.replicas(key, k)
+    }
+
+    /// See [`HashRing::replicas_owned`].
+    pub fn replicas_owned<K: Hash>(&self, key: &K, k: usize) -> Vec<OwnedRingToken<N>>
+    where
+        N: Clone,
+        P: Partitioner<K>,
+    {
+        self.ring.replicas_owned(key, k)
+    }
+
+    /// See [`HashRing::replicas_distinct`].
+    pub fn replicas_distinct<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.replicas_distinct(key, k)
+    }
+
+    /// See [`HashRing::replicas_distinct_by`].
+    pub fn replicas_distinct_by<K, I, F>(&self, key: &K, k: usize, id: F) -> Vec<RingToken<'_, N>>
+    where
+        K: Hash,
+        P: Partitioner<K>,
+        I: PartialEq,
+        F: Fn(&N) -> I,
+    {
+        self.ring.replicas_distinct_by(key, k, id)
+    }
+
+    /// See [`HashRing::replicas_diverse`].
+    pub fn replicas_diverse<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        N: ZonedNode,
+        P: Partitioner<K>,
+    {
+        self.ring.replicas_diverse(key, k)
+    }
+
+    /// See [`HashRing::replicas_diverse_by`].
+    pub fn replicas_diverse_by<K, Z, F>(&self, key: &K, k: usize, zone: F) -> Vec<RingToken<'_, N>>
+    where
+        K: Hash,
+        P: Partitioner<K>,
+        Z: PartialEq,
+        F: Fn(&N) -> Z,
+    {
+        self.ring.replicas_diverse_by(key, k, zone)
+    }
+
+    /// See [`HashRing::health`].
+    pub fn health(&self, node: &N) -> Option<NodeHealth> {
+        self.ring.health(node)
+    }
+
+    /// See [`HashRing::node_failover`].
+    pub fn node_failover<K: Hash>(&self, key: &K) -> Option<FailoverToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.node_failover(key)
+    }
+
+    /// See [`HashRing::replicas_failover`].
+    pub fn replicas_failover<K: Hash>(&self, key: &K, k: usize) -> Vec<FailoverToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.replicas_failover(key, k)
+    }
+
+    /// See [`HashRing::intervals`].
+    pub fn intervals(&self, node: &N) -> Option<Vec<KeyRange<RingPosition>>> {
+        self.ring.intervals(node)
+    }
+
+    /// See [`HashRing::ownership`].
+    pub fn ownership(&self) -> Vec<(KeyRange<RingPosition>, RingToken<'_, N>)> {
+        self.ring.ownership()
+    }
+
+    /// See [`HashRing::owns`].
+    pub fn owns<K: Hash>(&self, node: &N, key: &K) -> bool
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.owns(node, key)
+    }
+
+    /// See [`HashRing::position`].
+    pub fn position<K: Hash>(&self, key: &K) -> RingPosition
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.position(key)
+    }
+
+    /// See [`HashRing::node`].
+    pub fn node<K: Hash>(&self, key: &K) -> Option<RingToken<'_, N>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.node(key)
+    }
+
+    /// See [`HashRing::node_owned`].
+    pub fn node_owned<K: Hash>(&self, key: &K) -> Option<OwnedRingToken<N>>
+    where
+        N: Clone,
+        P: Partitioner<K>,
+    {
+        self.ring.node_owned(key)
+    }
+
+    /// See [`HashRing::node_batch`].
+    pub fn node_batch<K: Hash>(&self, keys: &[K]) -> Vec<Option<RingToken<'_, N>>>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.node_batch(keys)
+    }
+
+    /// See [`HashRing::group_by_node`].
+    pub fn group_by_node<'k, K: Hash>(&self, keys: &'k [K]) -> Vec<(RingToken<'_, N>, Vec<&'k K>)>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.group_by_node(keys)
+    }
+
+    /// See [`HashRing::probe_position`].
+    pub fn probe_position<K: Hash>(&self, key: &K) -> Option<RingPosition>
+    where
+        P: Partitioner<K>,
+    {
+        self.ring.probe_position(key)
+    }
+
+    /// See [`HashRing::key_range`].
+    pub fn key_range(&self, pos: RingPosition) -> Option<KeyRange<RingPosition>> {
+        self.ring.key_range(pos)
+    }
+
+    /// See [`HashRing::get`].
+    pub fn get(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        self.ring.get(pos)
+    }
+
+    /// See [`HashRing::token_of`].
+    pub fn token_of(&self, node: &N) -> Option<RingToken<'_, N>> {
+        self.ring.token_of(node)
+    }
+
+    /// See [`HashRing::contains`].
+    pub fn contains(&self, node: &N) -> bool {
+        self.ring.contains(node)
+    }
+
+    /// See [`HashRing::nodes`].
+    pub fn nodes(&self) -> impl DoubleEndedIterator<Item = RingToken<'_, N>> {
+        self.ring.nodes()
+    }
+
+    /// See [`HashRing::tokens`].
+    pub fn tokens(
+        &self,
+        start: RingPosition,
+        dir: RingDirection,
+    ) -> impl DoubleEndedIterator<Item = RingToken<'_, N>> {
+        self.ring.tokens(start, dir)
+    }
+
+    /// See [`HashRing::len`].
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// See [`HashRing::is_empty`].
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// See [`HashRing::diff`].
+    pub fn diff<Q>(&self, other: &RingSnapshot<N, Q>, replication_factor: usize) -> RingDiff<N>
+    where
+        N: Clone,
+        Q: Partitioner<N>,
+    {
+        self.ring.diff(&other.ring, replication_factor)
+    }
+}
@@ -0,0 +1,172 @@
+use {
+    crate::{
+        HashRing,
+        HashRingBuilder,
+        Partitioner,
+        RingDirection::Clockwise,
+        RingNode,
+        RingPosition,
+        StateError,
+    },
+    std::{collections::BTreeSet, sync::atomic::Ordering},
+};
+
+/// Plain representation of the ring state.
+///
+/// Allows to persist a ring or ship it to another process. Use
+/// [`HashRing::to_state`] and [`HashRing::from_state`] to convert between
+/// the ring and its state. With the `serde` feature enabled, both the state
+/// and the ring itself are serializable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RingState<N, P> {
+    /// The number of positions to probe for a given key.
+    pub probe_count: usize,
+
+    /// Partitioner used to compute ring positions.
+    pub partitioner: P,
+
+    /// Version of the ring.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub epoch: u64,
+
+    /// Tokens of the ring (ordered by position).
+    pub tokens: Vec<TokenState<N>>,
+}
+
+/// Plain representation of a ring token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TokenState<N> {
+    /// Position of the node on the ring.
+    pub position: RingPosition,
+
+    /// The node itself.
+    pub node: N,
+
+    /// Weight of the node.
+    #[cfg_attr(feature = "serde", serde(default = "default_weight"))]
+    pub weight: u32,
+
+    /// Whether the node was placed with [`HashRing::insert`].
+    ///
+    /// Positions of such nodes are not validated when restoring the ring.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inserted: bool,
+}
+
+#[cfg(feature = "serde")]
+const fn default_weight() -> u32 {
+    crate::DEFAULT_WEIGHT
+}
+
+impl<N, P> HashRing<N, P>
+where
+    N: RingNode + Clone,
+    P: Partitioner<N> + Clone,
+{
+    /// Returns the current state of the ring.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// ring.add(1);
+    /// ring.insert(42, 2);
+    ///
+    /// let state = ring.to_state();
+    /// assert_eq!(state.tokens.len(), 2);
+    ///
+    /// let restored = mpchash::HashRing::from_state(state).expect("valid state");
+    /// assert_eq!(restored.len(), 2);
+    /// assert_eq!(restored.epoch(), ring.epoch());
+    /// ```
+    pub fn to_state(&self) -> RingState<N, P> {
+        let ring = self.duplicate();
+        let tokens = ring
+            .tokens(0, Clockwise)
+            .map(|token| {
+                let position = token.position();
+                let inserted = ring
+                    .candidate_positions(token.node())
+                    .all(|pos| pos != position);
+                TokenState {
+                    position,
+                    node: token.node().clone(),
+                    weight: ring.weight_at(position),
+                    inserted,
+                }
+            })
+            .collect();
+        RingState {
+            probe_count: ring.probe_count,
+            partitioner: ring.partitioner.clone(),
+            epoch: ring.epoch(),
+            tokens,
+        }
+    }
+
+    /// Restores the ring from its state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StateError`] if the state is invalid: probe count or some
+    /// weight is zero, several nodes share a position, or a node position
+    /// doesn't match the partitioner (unless the node is marked as
+    /// [`inserted`](TokenState::inserted)).
+    pub fn from_state(state: RingState<N, P>) -> Result<Self, StateError> {
+        if state.probe_count == 0 {
+            return Err(StateError::ZeroProbeCount);
+        }
+        let ring = HashRingBuilder::new()
+            .partitioner(state.partitioner)
+            .probe_count(state.probe_count)
+            .build();
+
+        let mut positions = BTreeSet::new();
+        for token in &state.tokens {
+            if token.weight == 0 {
+                return Err(StateError::ZeroWeight(token.position));
+            }
+            if !positions.insert(token.position) {
+                return Err(StateError::DuplicatePosition(token.position));
+            }
+            if !token.inserted
+                && ring
+                    .candidate_positions(&token.node)
+                    .all(|pos| pos != token.position)
+            {
+                return Err(StateError::PositionMismatch(token.position));
+            }
+        }
+
+        for token in state.tokens {
+            ring.insert_weighted(token.position, token.node, token.weight);
+        }
+        ring.epoch.store(state.epoch, Ordering::SeqCst);
+        Ok(ring)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N, P> serde::Serialize for HashRing<N, P>
+where
+    N: RingNode + Clone + serde::Serialize,
+    P: Partitioner<N> + Clone + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_state().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, N, P> serde::Deserialize<'de> for HashRing<N, P>
+where
+    N: RingNode + Clone + serde::Deserialize<'de>,
+    P: Partitioner<N> + Clone + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = RingState::deserialize(deserializer)?;
+        Self::from_state(state).map_err(serde::de::Error::custom)
+    }
+}
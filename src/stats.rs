@@ -0,0 +1,179 @@
+use {
+    crate::{HashRing, Partitioner, RingNode, RingPosition},
+    std::{
+        collections::HashMap,
+        hash::{BuildHasher, Hash, RandomState},
+    },
+};
+
+/// Load of a single node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStats<N> {
+    /// The node.
+    pub node: N,
+
+    /// Positions of the node on the ring (in ascending order).
+    ///
+    /// A node is normally located at a single position, but may be placed at
+    /// several ones (see [`HashRing::insert`]).
+    pub positions: Vec<RingPosition>,
+
+    /// Weight of the node, summed over all of its positions.
+    pub weight: u32,
+
+    /// Number of sampled keys owned by the node.
+    pub keys: usize,
+
+    /// Fraction of the ring covered by the node's intervals.
+    ///
+    /// Note that, with multi-probe hashing, the fraction of keys owned by a
+    /// node is not proportional to its interval: probing evens out the load
+    /// of nodes with short and long intervals.
+    pub keyspace: f64,
+}
+
+/// Distribution of keys over the nodes of a ring.
+///
+/// See [`HashRing::stats`] for details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadStats<N> {
+    /// Per-node loads (ordered by the first position of the node).
+    pub nodes: Vec<NodeStats<N>>,
+
+    /// Total number of sampled keys.
+    pub total_keys: usize,
+}
+
+impl<N> LoadStats<N> {
+    /// Returns the average number of keys per node.
+    pub fn mean(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        self.total_keys as f64 / self.nodes.len() as f64
+    }
+
+    /// Returns the standard deviation of the number of keys per node.
+    pub fn std_dev(&self) -> f64 {
+        if self.nodes.is_empty() {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let variance = self
+            .nodes
+            .iter()
+            .map(|stats| (stats.keys as f64 - mean).powi(2))
+            .sum::<f64>()
+            / self.nodes.len() as f64;
+        variance.sqrt()
+    }
+
+    /// Returns the peak-to-average load ratio.
+    ///
+    /// Load of each node is compared to its fair share of keys, i.e.
+    /// proportional to the node weight. So, for a perfectly balanced ring the
+    /// ratio is `1.0`, regardless of weights. Returns `0.0` if no keys are
+    /// sampled.
+    pub fn peak_to_average(&self) -> f64 {
+        let total_weight = self
+            .nodes
+            .iter()
+            .map(|stats| f64::from(stats.weight))
+            .sum::<f64>();
+        if self.total_keys == 0 || total_weight == 0.0 {
+            return 0.0;
+        }
+        self.nodes
+            .iter()
+            .map(|stats| {
+                let share = self.total_keys as f64 * f64::from(stats.weight) / total_weight;
+                stats.keys as f64 / share
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+impl<N, P> HashRing<N, P>
+where
+    N: RingNode + Clone,
+    P: Partitioner<N> + Clone,
+{
+    /// Returns the distribution of the given keys over the nodes.
+    ///
+    /// Statistics are computed over a consistent copy of the ring, so
+    /// concurrent modifications do not affect the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ring = mpchash::HashRing::<u64>::new();
+    /// (0..10).for_each(|i| ring.add(i));
+    ///
+    /// let stats = ring.stats(0..10_000u64);
+    /// assert_eq!(stats.total_keys, 10_000);
+    /// assert_eq!(stats.nodes.len(), 10);
+    /// assert!(stats.peak_to_average() < 1.5);
+    /// ```
+    pub fn stats<K, I>(&self, keys: I) -> LoadStats<N>
+    where
+        K: Hash,
+        I: IntoIterator<Item = K>,
+        P: Partitioner<K>,
+    {
+        let ring = self.duplicate();
+
+        // Tokens are aggregated by node. Nodes are only required to be
+        // `Hash + PartialEq`, so they are bucketed by hash, and compared for
+        // equality within a bucket.
+        let hasher = RandomState::new();
+        let mut buckets = HashMap::<u64, Vec<usize>>::new();
+        let mut nodes = Vec::<NodeStats<N>>::new();
+        let mut owners = Vec::new();
+        for (range, token) in ring.ownership() {
+            let bucket = buckets.entry(hasher.hash_one(token.node())).or_default();
+            let idx = match bucket.iter().find(|idx| nodes[**idx].node == *token.node()) {
+                Some(idx) => *idx,
+                None => {
+                    bucket.push(nodes.len());
+                    nodes.push(NodeStats {
+                        node: token.node().clone(),
+                        positions: Vec::new(),
+                        weight: 0,
+                        keys: 0,
+                        keyspace: 0.0,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            let stats = &mut nodes[idx];
+            stats.positions.push(token.position());
+            stats.weight += ring.weight_at(token.position());
+            stats.keyspace += range.size() as f64 / RingPosition::MAX as f64;
+            owners.push((token.position(), idx));
+        }
+
+        let mut total_keys = 0;
+        for key in keys {
+            let Some(token) = ring.node(&key) else {
+                break;
+            };
+            if let Ok(owner) = owners.binary_search_by_key(&token.position(), |(pos, _)| *pos) {
+                nodes[owners[owner].1].keys += 1;
+            }
+            total_keys += 1;
+        }
+        LoadStats { nodes, total_keys }
+    }
+
+    /// Returns the distribution of a synthetic uniform sample of keys over the
+    /// nodes.
+    ///
+    /// Keys `0..samples` are used, which, once hashed, are spread uniformly
+    /// over the ring. See [`stats()`](Self::stats) for details.
+    pub fn stats_uniform(&self, samples: usize) -> LoadStats<N>
+    where
+        P: Partitioner<u64>,
+    {
+        self.stats(0..samples as u64)
+    }
+}
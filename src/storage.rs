@@ -0,0 +1,417 @@
+mod sorted_array;
+
+use {
+    crate::{token::TokenRef, RingNode, RingPosition, RingToken, DEFAULT_WEIGHT},
+    crossbeam_skiplist::SkipMap,
+    sorted_array::SortedArray,
+    std::{
+        ops::{
+            Bound::{Excluded, Unbounded},
+            RangeBounds,
+        },
+        sync::Arc,
+    },
+};
+
+/// Storage backend of a [`HashRing`](crate::HashRing).
+///
+/// Node positions are kept either in a concurrent skip list
+/// ([`SkipMapStorage`], the default), or in an immutable sorted array which
+/// is replaced on every modification ([`SortedArrayStorage`]). Use
+/// [`HashRingBuilder::storage`](crate::HashRingBuilder::storage) to select
+/// the backend.
+///
+/// The trait is sealed: it can't be implemented outside of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use mpchash::{HashRingBuilder, SortedArrayStorage};
+///
+/// let ring = HashRingBuilder::new()
+///     .storage(SortedArrayStorage)
+///     .build::<u64>();
+/// (0..10).for_each(|node| ring.add(node));
+/// assert!(ring.node(&"key").is_some());
+/// ```
+pub trait RingStorage: private::Sealed {
+    /// Creates an empty storage for nodes of type `N`.
+    #[doc(hidden)]
+    fn positions<N: RingNode>(&self) -> Positions<N>;
+}
+
+mod private {
+    /// Prevents implementations of [`RingStorage`](super::RingStorage) outside
+    /// of this crate.
+    pub trait Sealed {}
+
+    impl Sealed for super::SkipMapStorage {}
+    impl Sealed for super::SortedArrayStorage {}
+}
+
+/// Skip list storage (the default).
+///
+/// Modifications are lock-free and don't block each other, but every lookup
+/// probe is a search through the skip list (pointer chasing).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SkipMapStorage;
+
+impl RingStorage for SkipMapStorage {
+    fn positions<N: RingNode>(&self) -> Positions<N> {
+        Positions::SkipMap {
+            nodes: SkipMap::new(),
+            weights: SkipMap::new(),
+        }
+    }
+}
+
+/// Sorted array storage, optimized for lookups.
+///
+/// Node positions are kept in a contiguous array (in the Eytzinger order),
+/// which is searched without branches, so lookups are considerably faster
+/// than with [`SkipMapStorage`]. The array is never modified in place: every
+/// modification copies it, and atomically replaces the current one
+/// (read-copy-update). Thus, lookups are never blocked, while modifications
+/// take `O(n)` time and are serialized. Prefer this storage when membership
+/// changes are rare compared to lookups.
+///
+/// Nodes are shared between the array versions, so tokens remain valid
+/// after their nodes are removed, and snapshots are cheap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SortedArrayStorage;
+
+impl RingStorage for SortedArrayStorage {
+    fn positions<N: RingNode>(&self) -> Positions<N> {
+        Positions::SortedArray(SortedArray::new())
+    }
+}
+
+/// Node positions, sorted in ascending order.
+///
+/// Positions are allocated once per ring (behind an `Arc`), so the size of the
+/// skip list head is not an issue, while boxing it would cost an extra
+/// indirection on every lookup.
+#[allow(clippy::large_enum_variant)]
+pub enum Positions<N> {
+    SkipMap {
+        nodes: SkipMap<RingPosition, Arc<N>>,
+        /// Weights of the nodes, other than the default one.
+        weights: SkipMap<RingPosition, u32>,
+    },
+    SortedArray(SortedArray<N>),
+}
+
+impl<N: RingNode> Positions<N> {
+    /// Returns the number of positions.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::SkipMap { nodes, .. } => nodes.len(),
+            Self::SortedArray(array) => array.len(),
+        }
+    }
+
+    /// Returns `true` if there are no positions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if a node is located at the given position.
+    pub fn contains(&self, pos: RingPosition) -> bool {
+        match self {
+            Self::SkipMap { nodes, .. } => nodes.contains_key(&pos),
+            Self::SortedArray(array) => array.contains(pos),
+        }
+    }
+
+    /// Returns the token of the node located at the given position.
+    pub fn get(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        match self {
+            Self::SkipMap { nodes, .. } => nodes.get(&pos).map(Into::into),
+            Self::SortedArray(array) => array.get(pos).map(|node| RingToken::shared(pos, node)),
+        }
+    }
+
+    /// Returns the weight of the node located at the given position.
+    pub fn weight(&self, pos: RingPosition) -> u32 {
+        match self {
+            Self::SkipMap { weights, .. } if weights.is_empty() => DEFAULT_WEIGHT,
+            Self::SkipMap { weights, .. } => weights
+                .get(&pos)
+                .map_or(DEFAULT_WEIGHT, |entry| *entry.value()),
+            Self::SortedArray(array) => array.weight(pos).unwrap_or(DEFAULT_WEIGHT),
+        }
+    }
+
+    /// Returns the sum of weights of all the nodes.
+    pub fn total_weight(&self) -> u64 {
+        match self {
+            Self::SkipMap { nodes, weights } if weights.is_empty() => nodes.len() as u64,
+            Self::SkipMap { nodes, .. } => nodes
+                .iter()
+                .map(|entry| u64::from(self.weight(*entry.key())))
+                .sum(),
+            Self::SortedArray(array) => array.total_weight(),
+        }
+    }
+
+    /// Places a node with a given weight at the given position, replacing the
+    /// node located there.
+    ///
+    /// Returns the token of the placed node, and tokens of the replaced ones.
+    /// Whenever nodes are concurrently placed at the same position, more than
+    /// one node might be replaced.
+    pub fn insert(
+        &self,
+        pos: RingPosition,
+        node: N,
+        weight: u32,
+    ) -> (RingToken<'_, N>, Vec<RingToken<'_, N>>) {
+        match self {
+            Self::SkipMap { nodes, weights } => {
+                Self::set_weight(weights, pos, weight);
+                let node = Arc::new(node);
+                let mut replaced = Vec::new();
+                loop {
+                    let entry = nodes.get_or_insert(pos, Arc::clone(&node));
+                    if Arc::ptr_eq(entry.value(), &node) {
+                        return (entry.into(), replaced);
+                    }
+                    // A node removed concurrently is not replaced by this call.
+                    // (Replacing with `SkipMap::insert` removes the old entry
+                    // first as well.)
+                    if entry.remove() {
+                        replaced.push(entry.into());
+                    }
+                }
+            }
+            Self::SortedArray(array) => {
+                let (node, old) = array.insert(pos, node, weight);
+                let replaced = old.map(|old| RingToken::shared(pos, old));
+                (RingToken::shared(pos, node), replaced.into_iter().collect())
+            }
+        }
+    }
+
+    /// Places a node with a given weight at the given position, unless the
+    /// position is already taken, in which case the node is given back.
+    ///
+    /// Checking the position and placing the node is a single atomic step.
+    /// Skip list nodes are kept behind an `Arc`, so that the node can be told
+    /// apart from a concurrently placed one, and given back.
+    pub fn try_insert(
+        &self,
+        pos: RingPosition,
+        node: N,
+        weight: u32,
+    ) -> Result<RingToken<'_, N>, N> {
+        match self {
+            Self::SkipMap { nodes, weights } => {
+                let node = Arc::new(node);
+                let entry = nodes.get_or_insert(pos, Arc::clone(&node));
+                if Arc::ptr_eq(entry.value(), &node) {
+                    Self::set_weight(weights, pos, weight);
+                    return Ok(entry.into());
+                }
+                // The clone passed to the skip list is dropped, once it is
+                // found that the position is taken.
+                drop(entry);
+                Err(Arc::into_inner(node).expect("rejected node is not shared"))
+            }
+            Self::SortedArray(array) => array
+                .try_insert(pos, node, weight)
+                .map(|node| RingToken::shared(pos, node)),
+        }
+    }
+
+    /// Removes the given token, unless it was already removed or replaced.
+    ///
+    /// Returns `true` if the token was removed by this call.
+    pub fn remove(&self, token: &RingToken<'_, N>) -> bool {
+        match (self, &token.0) {
+            (Self::SkipMap { weights, .. }, TokenRef::Entry(entry)) => {
+                let removed = entry.remove();
+                if removed {
+                    weights.remove(entry.key());
+                }
+                removed
+            }
+            (Self::SortedArray(array), TokenRef::Shared(pos, node)) => array.remove(*pos, node),
+            _ => false,
+        }
+    }
+
+    /// Returns the token of the first node located strictly after the given
+    /// position, wrapping around to the first node.
+    pub fn owner(&self, pos: RingPosition) -> Option<RingToken<'_, N>> {
+        let view = self.view();
+        view.owner(pos).map(|owner| view.token(owner))
+    }
+
+    /// Returns a view of the positions, for resolving owners of several
+    /// positions.
+    ///
+    /// Tokens are only created for the owners which are actually needed, as
+    /// for a sorted array, creating a token is more expensive than the search.
+    pub fn view(&self) -> View<'_, N> {
+        match self {
+            Self::SkipMap { nodes, weights } => View::SkipMap { nodes, weights },
+            Self::SortedArray(array) => View::SortedArray(array.view()),
+        }
+    }
+
+    /// Returns tokens of the nodes located within the given range.
+    pub fn range<R: RangeBounds<RingPosition>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = RingToken<'_, N>> {
+        match self {
+            Self::SkipMap { nodes, .. } => Iter::SkipMap(nodes.range(range).map(Into::into)),
+            Self::SortedArray(array) => Iter::SortedArray(
+                array
+                    .range(range)
+                    .map(|(pos, node)| RingToken::shared(pos, node)),
+            ),
+        }
+    }
+
+    /// Returns tokens of all the nodes.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = RingToken<'_, N>> {
+        self.range(..)
+    }
+
+    /// Returns a copy of the positions, using the same backend.
+    pub fn duplicate(&self) -> Self {
+        match self {
+            Self::SkipMap { nodes, weights } => {
+                let copy = SkipMap::new();
+                for entry in nodes {
+                    copy.insert(*entry.key(), Arc::clone(entry.value()));
+                }
+                let weights_copy = SkipMap::new();
+                for entry in weights {
+                    weights_copy.insert(*entry.key(), *entry.value());
+                }
+                Self::SkipMap {
+                    nodes: copy,
+                    weights: weights_copy,
+                }
+            }
+            Self::SortedArray(array) => Self::SortedArray(array.duplicate()),
+        }
+    }
+
+    /// Sets the weight of a skip list node (only non-default weights are kept).
+    fn set_weight(weights: &SkipMap<RingPosition, u32>, pos: RingPosition, weight: u32) {
+        if weight == DEFAULT_WEIGHT {
+            weights.remove(&pos);
+        } else {
+            weights.insert(pos, weight);
+        }
+    }
+}
+
+/// View of the positions, see [`Positions::view`].
+pub enum View<'a, N> {
+    SkipMap {
+        nodes: &'a SkipMap<RingPosition, Arc<N>>,
+        weights: &'a SkipMap<RingPosition, u32>,
+    },
+    SortedArray(sorted_array::View<N>),
+}
+
+/// Owner of a position, resolved with a [`View`].
+pub enum Owner<'a, N> {
+    Token(RingToken<'a, N>),
+    Index(RingPosition, usize),
+}
+
+impl<N> Clone for Owner<'_, N> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Token(token) => Self::Token(token.clone()),
+            Self::Index(pos, index) => Self::Index(*pos, *index),
+        }
+    }
+}
+
+impl<N: RingNode> Owner<'_, N> {
+    /// Returns the position of the owner.
+    pub fn position(&self) -> RingPosition {
+        match self {
+            Self::Token(token) => token.position(),
+            Self::Index(pos, _) => *pos,
+        }
+    }
+}
+
+impl<'a, N: RingNode> View<'a, N> {
+    /// Returns the first node located strictly after the given position,
+    /// wrapping around to the first node.
+    pub fn owner(&self, pos: RingPosition) -> Option<Owner<'a, N>> {
+        match self {
+            Self::SkipMap { nodes, .. } => nodes
+                .range((Excluded(pos), Unbounded))
+                .next()
+                .or_else(|| nodes.front())
+                .map(|entry| Owner::Token(entry.into())),
+            Self::SortedArray(view) => view.owner(pos).map(|(pos, index)| Owner::Index(pos, index)),
+        }
+    }
+
+    /// Returns the token of the owner.
+    pub fn token(&self, owner: Owner<'a, N>) -> RingToken<'a, N> {
+        match (self, owner) {
+            (_, Owner::Token(token)) => token,
+            (Self::SortedArray(view), Owner::Index(pos, index)) => {
+                RingToken::shared(pos, view.node(index))
+            }
+            (Self::SkipMap { .. }, Owner::Index(..)) => unreachable!("index of a skip list"),
+        }
+    }
+
+    /// Returns the weight of the owner.
+    pub fn weight(&self, owner: &Owner<'a, N>) -> u32 {
+        match (self, owner) {
+            (Self::SkipMap { weights, .. }, _) if weights.is_empty() => DEFAULT_WEIGHT,
+            (Self::SkipMap { weights, .. }, owner) => weights
+                .get(&owner.position())
+                .map_or(DEFAULT_WEIGHT, |entry| *entry.value()),
+            (Self::SortedArray(view), Owner::Index(_, index)) => view.weight(*index),
+            (Self::SortedArray(_), Owner::Token(_)) => unreachable!("token of a sorted array"),
+        }
+    }
+}
+
+/// Iterator over the positions of either of the backends.
+enum Iter<A, B> {
+    SkipMap(A),
+    SortedArray(B),
+}
+
+impl<A, B, V> Iterator for Iter<A, B>
+where
+    A: Iterator<Item = V>,
+    B: Iterator<Item = V>,
+{
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::SkipMap(iter) => iter.next(),
+            Self::SortedArray(iter) => iter.next(),
+        }
+    }
+}
+
+impl<A, B, V> DoubleEndedIterator for Iter<A, B>
+where
+    A: DoubleEndedIterator<Item = V>,
+    B: DoubleEndedIterator<Item = V>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::SkipMap(iter) => iter.next_back(),
+            Self::SortedArray(iter) => iter.next_back(),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use {
+    crate::RingPosition,
+    arc_swap::{ArcSwap, Guard},
+    std::{
+        ops::{Bound, RangeBounds},
+        sync::{Arc, Mutex, PoisonError},
+    },
+};
+
+/// Node positions kept in an immutable sorted array, which is replaced on
+/// every modification (read-copy-update).
+pub struct SortedArray<N> {
+    /// The current version of the array.
+    layout: ArcSwap<Layout<N>>,
+
+    /// Serializes modifications, so that none of them is lost.
+    writer: Mutex<()>,
+}
+
+/// Current version of the array, held while resolving several positions.
+pub struct View<N>(Guard<Arc<Layout<N>>>);
+
+/// Immutable version of the array.
+struct Layout<N> {
+    /// Node positions, in ascending order.
+    positions: Vec<RingPosition>,
+
+    /// Nodes, in the order of positions.
+    nodes: Vec<Arc<N>>,
+
+    /// Weights of the nodes, in the order of positions.
+    weights: Vec<u32>,
+
+    /// Node positions in the Eytzinger order (i.e. an implicit binary search
+    /// tree, where children of `k` are `2k` and `2k + 1`), starting at one.
+    tree: Vec<RingPosition>,
+
+    /// Indexes into `positions` of the `tree` items.
+    index: Vec<usize>,
+}
+
+/// Modifiable copy of the array (sorted positions, their nodes and weights).
+struct Columns<N> {
+    positions: Vec<RingPosition>,
+    nodes: Vec<Arc<N>>,
+    weights: Vec<u32>,
+}
+
+impl<N> Default for Columns<N> {
+    fn default() -> Self {
+        Self {
+            positions: Vec::new(),
+            nodes: Vec::new(),
+            weights: Vec::new(),
+        }
+    }
+}
+
+impl<N> SortedArray<N> {
+    /// Creates an empty array.
+    pub fn new() -> Self {
+        Self {
+            layout: ArcSwap::from_pointee(Layout::new(Columns::default())),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Returns a copy of the array, sharing the current version with it.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            layout: ArcSwap::new(self.layout.load_full()),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Returns the number of positions.
+    pub fn len(&self) -> usize {
+        self.layout.load().positions.len()
+    }
+
+    /// Returns `true` if a node is located at the given position.
+    pub fn contains(&self, pos: RingPosition) -> bool {
+        self.layout.load().positions.binary_search(&pos).is_ok()
+    }
+
+    /// Returns the node located at the given position.
+    pub fn get(&self, pos: RingPosition) -> Option<Arc<N>> {
+        let layout = self.layout.load();
+        let index = layout.positions.binary_search(&pos).ok()?;
+        Some(Arc::clone(&layout.nodes[index]))
+    }
+
+    /// Returns the weight of the node located at the given position.
+    pub fn weight(&self, pos: RingPosition) -> Option<u32> {
+        let layout = self.layout.load();
+        let index = layout.positions.binary_search(&pos).ok()?;
+        Some(layout.weights[index])
+    }
+
+    /// Returns the sum of weights of all the nodes.
+    pub fn total_weight(&self) -> u64 {
+        self.layout
+            .load()
+            .weights
+            .iter()
+            .copied()
+            .map(u64::from)
+            .sum()
+    }
+
+    /// Returns the current version of the array.
+    pub fn view(&self) -> View<N> {
+        View(self.layout.load())
+    }
+
+    /// Places a node with a given weight at the given position, replacing the
+    /// node located there.
+    ///
+    /// Returns the placed node, and the replaced one (if any).
+    pub fn insert(&self, pos: RingPosition, node: N, weight: u32) -> (Arc<N>, Option<Arc<N>>) {
+        let node = Arc::new(node);
+        let old = self.update(|columns| match columns.positions.binary_search(&pos) {
+            Ok(index) => {
+                columns.weights[index] = weight;
+                Some(std::mem::replace(
+                    &mut columns.nodes[index],
+                    Arc::clone(&node),
+                ))
+            }
+            Err(index) => {
+                columns.insert(index, pos, Arc::clone(&node), weight);
+                None
+            }
+        });
+        (node, old)
+    }
+
+    /// Places a node with a given weight at the given position, unless the
+    /// position is already taken, in which case the node is given back.
+    pub fn try_insert(&self, pos: RingPosition, node: N, weight: u32) -> Result<Arc<N>, N> {
+        let mut node = Some(node);
+        self.update(|columns| {
+            let index = columns.positions.binary_search(&pos).err()?;
+            let node = Arc::new(node.take().expect("node is placed once"));
+            columns.insert(index, pos, Arc::clone(&node), weight);
+            Some(node)
+        })
+        .ok_or_else(|| node.take().expect("rejected node is given back"))
+    }
+
+    /// Removes the given node, unless it is no longer located at the given
+    /// position.
+    ///
+    /// Returns `true` if the node was removed.
+    pub fn remove(&self, pos: RingPosition, node: &Arc<N>) -> bool {
+        self.update(|columns| {
+            let index = columns.positions.binary_search(&pos).ok()?;
+            Arc::ptr_eq(&columns.nodes[index], node).then(|| columns.remove(index))
+        })
+        .is_some()
+    }
+
+    /// Returns the nodes located within the given range.
+    pub fn range<R: RangeBounds<RingPosition>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (RingPosition, Arc<N>)> {
+        let layout = self.layout.load_full();
+        let positions = &layout.positions;
+        let start = match range.start_bound() {
+            Bound::Included(start) => positions.partition_point(|pos| pos < start),
+            Bound::Excluded(start) => positions.partition_point(|pos| pos <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => positions.partition_point(|pos| pos <= end),
+            Bound::Excluded(end) => positions.partition_point(|pos| pos < end),
+            Bound::Unbounded => positions.len(),
+        };
+        (start..end.max(start))
+            .map(move |index| (layout.positions[index], Arc::clone(&layout.nodes[index])))
+    }
+
+    /// Replaces the current version of the array with its modified copy.
+    fn update<F, R>(&self, modify: F) -> R
+    where
+        F: FnOnce(&mut Columns<N>) -> R,
+    {
+        let _guard = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = self.layout.load();
+        let mut columns = Columns {
+            positions: current.positions.clone(),
+            nodes: current.nodes.clone(),
+            weights: current.weights.clone(),
+        };
+        let result = modify(&mut columns);
+        self.layout.store(Arc::new(Layout::new(columns)));
+        result
+    }
+}
+
+impl<N> View<N> {
+    /// Returns the position and the index of the first node located strictly
+    /// after the given position, wrapping around to the first node.
+    pub fn owner(&self, pos: RingPosition) -> Option<(RingPosition, usize)> {
+        let index = self.0.successor(pos)?;
+        Some((self.0.positions[index], index))
+    }
+
+    /// Returns the node at the given index.
+    pub fn node(&self, index: usize) -> Arc<N> {
+        Arc::clone(&self.0.nodes[index])
+    }
+
+    /// Returns the weight of the node at the given index.
+    pub fn weight(&self, index: usize) -> u32 {
+        self.0.weights[index]
+    }
+}
+
+impl<N> Columns<N> {
+    fn insert(&mut self, index: usize, pos: RingPosition, node: Arc<N>, weight: u32) {
+        self.positions.insert(index, pos);
+        self.nodes.insert(index, node);
+        self.weights.insert(index, weight);
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.positions.remove(index);
+        self.nodes.remove(index);
+        self.weights.remove(index);
+    }
+}
+
+impl<N> Layout<N> {
+    /// Creates a version of the array from sorted positions, their nodes and
+    /// weights.
+    fn new(columns: Columns<N>) -> Self {
+        let Columns {
+            positions,
+            nodes,
+            weights,
+        } = columns;
+        let mut layout = Self {
+            tree: vec![0; positions.len() + 1],
+            index: vec![0; positions.len() + 1],
+            positions,
+            nodes,
+            weights,
+        };
+        layout.fill(1, &mut 0);
+        layout
+    }
+
+    /// Fills the subtree rooted at `k` (in-order traversal of the tree visits
+    /// positions in ascending order).
+    fn fill(&mut self, k: usize, next: &mut usize) {
+        if k < self.tree.len() {
+            self.fill(2 * k, next);
+            self.tree[k] = self.positions[*next];
+            self.index[k] = *next;
+            *next += 1;
+            self.fill(2 * k + 1, next);
+        }
+    }
+
+    /// Returns the index of the first position strictly after the given one,
+    /// wrapping around to the first position.
+    fn successor(&self, pos: RingPosition) -> Option<usize> {
+        if self.positions.is_empty() {
+            return None;
+        }
+        // Descend the tree without branching: go right whenever the position
+        // of the current item is not after the given one.
+        let mut k = 1;
+        while k < self.tree.len() {
+            k = 2 * k + usize::from(self.tree[k] <= pos);
+        }
+        // The successor is the item where we last went left, i.e. strip the
+        // trailing right turns, and the left turn itself.
+        k >>= k.trailing_ones() + 1;
+        Some(if k == 0 { 0 } else { self.index[k] })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rand::random};
+
+    #[test]
+    fn successor() {
+        for len in 0..70 {
+            let mut positions = (0..len).map(|_| random::<u64>() % 1000).collect::<Vec<_>>();
+            positions.sort_unstable();
+            positions.dedup();
+            let layout = Layout::new(Columns {
+                nodes: positions.iter().map(|pos| Arc::new(*pos)).collect(),
+                weights: vec![1; positions.len()],
+                positions: positions.clone(),
+            });
+
+            for pos in (0..1001).chain([u64::MAX]) {
+                let expected = match positions.partition_point(|p| *p <= pos) {
+                    _ if positions.is_empty() => None,
+                    index if index == positions.len() => Some(0),
+                    index => Some(index),
+                };
+                assert_eq!(layout.successor(pos), expected, "{pos} in {positions:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn modifications() {
+        let array = SortedArray::new();
+        assert!(array.view().owner(0).is_none());
+
+        let (node, old) = array.insert(20, 2, 1);
+        assert!(old.is_none());
+        array.insert(10, 1, 1);
+        array.insert(30, 3, 2);
+        assert_eq!(array.len(), 3);
+        assert!(array.contains(20));
+        assert_eq!(array.view().owner(10), Some((20, 1)));
+        assert_eq!(array.view().owner(30), Some((10, 0)));
+        assert_eq!(array.view().weight(2), 2);
+        assert_eq!(array.weight(30), Some(2));
+        assert_eq!(array.weight(40), None);
+        assert_eq!(array.total_weight(), 4);
+
+        let range = |range| array.range(range).map(|(pos, _)| pos).collect::<Vec<_>>();
+        assert_eq!(range((Bound::Excluded(10), Bound::Included(30))), vec![
+            20, 30
+        ]);
+        assert_eq!(range((Bound::Included(11), Bound::Excluded(30))), vec![20]);
+        assert_eq!(range((Bound::Excluded(30), Bound::Unbounded)), vec![]);
+
+        // Replaced nodes are not removed.
+        let copy = array.duplicate();
+        let (_, old) = array.insert(20, 4, 3);
+        assert!(old.is_some_and(|old| Arc::ptr_eq(&old, &node)));
+        assert!(!array.remove(20, &node));
+        assert_eq!(array.get(20).as_deref(), Some(&4));
+        assert_eq!(array.weight(20), Some(3));
+        assert_eq!(copy.get(20).as_deref(), Some(&2));
+        assert_eq!(copy.weight(20), Some(1));
+
+        let node = array.get(20).unwrap();
+        assert!(array.remove(20, &node));
+        assert!(!array.remove(20, &node));
+        assert!(!array.contains(20));
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.total_weight(), 3);
+        assert_eq!(copy.len(), 3);
+    }
+}
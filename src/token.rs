@@ -1,26 +1,79 @@
 use {
     crate::{RingNode, RingPosition},
     crossbeam_skiplist::map::Entry,
-    std::{borrow::Borrow, ops::Deref},
+    std::{borrow::Borrow, ops::Deref, sync::Arc},
 };
 
 /// An ownership over a position on the ring by the object of type `T`
 /// (normally, `RingNode`).
 ///
-/// Wrapper around `crossbeam_skiplist::map::Entry` that allows to obtain
-/// entry's key/value as references.
-#[derive(Clone, Debug)]
-pub struct RingToken<'a, T>(Entry<'a, RingPosition, T>);
+/// Wrapper around `crossbeam_skiplist::map::Entry` (or around a shared node,
+/// for rings backed by [`SortedArrayStorage`]) that allows to obtain entry's
+/// key/value as references.
+///
+/// [`SortedArrayStorage`]: crate::SortedArrayStorage
+#[derive(Debug)]
+pub struct RingToken<'a, T>(pub(crate) TokenRef<'a, T>);
+
+/// Reference to a node stored in one of the ring storage backends.
+#[derive(Debug)]
+pub(crate) enum TokenRef<'a, T> {
+    /// Entry of the skip list.
+    Entry(Entry<'a, RingPosition, Arc<T>>),
+
+    /// Node shared with an immutable sorted array.
+    Shared(RingPosition, Arc<T>),
+}
+
+impl<T> Clone for RingToken<'_, T> {
+    fn clone(&self) -> Self {
+        Self(match &self.0 {
+            TokenRef::Entry(entry) => TokenRef::Entry(entry.clone()),
+            TokenRef::Shared(position, node) => TokenRef::Shared(*position, Arc::clone(node)),
+        })
+    }
+}
+
+impl<T> RingToken<'_, T> {
+    /// Creates a token for a node shared with an immutable sorted array.
+    pub(crate) const fn shared(position: RingPosition, node: Arc<T>) -> Self {
+        Self(TokenRef::Shared(position, node))
+    }
+
+    /// Returns a reference to the node.
+    fn value(&self) -> &T {
+        match &self.0 {
+            TokenRef::Entry(entry) => entry.value(),
+            TokenRef::Shared(_, node) => node,
+        }
+    }
+}
 
 impl<T: RingNode> RingToken<'_, T> {
     /// Return the position of the node on the ring.
     pub fn position(&self) -> RingPosition {
-        *self.0.key()
+        match &self.0 {
+            TokenRef::Entry(entry) => *entry.key(),
+            TokenRef::Shared(position, _) => *position,
+        }
     }
 
     /// Return the node that owns this token.
     pub fn node(&self) -> &T {
-        self.0.value()
+        self.value()
+    }
+}
+
+impl<T: RingNode + Clone> RingToken<'_, T> {
+    /// Converts the token into an owned one, cloning the node.
+    ///
+    /// Owned token doesn't borrow from the ring, so it can be stored or sent
+    /// to other threads.
+    pub fn into_owned(self) -> OwnedRingToken<T> {
+        OwnedRingToken {
+            position: self.position(),
+            node: self.node().clone(),
+        }
     }
 }
 
@@ -28,25 +81,25 @@ impl<T> Deref for RingToken<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.0.value()
+        self.value()
     }
 }
 
 impl<T> AsRef<T> for RingToken<'_, T> {
     fn as_ref(&self) -> &T {
-        self.0.value()
+        self.value()
     }
 }
 
 impl<T> Borrow<T> for RingToken<'_, T> {
     fn borrow(&self) -> &T {
-        self.0.value()
+        self.value()
     }
 }
 
-impl<'a, T> From<Entry<'a, RingPosition, T>> for RingToken<'a, T> {
-    fn from(entry: Entry<'a, RingPosition, T>) -> Self {
-        Self(entry)
+impl<'a, T> From<Entry<'a, RingPosition, Arc<T>>> for RingToken<'a, T> {
+    fn from(entry: Entry<'a, RingPosition, Arc<T>>) -> Self {
+        Self(TokenRef::Entry(entry))
     }
 }
 
@@ -87,3 +140,97 @@ impl<T: RingNode> Ord for RingToken<'_, T> {
         self.position().cmp(&other.position())
     }
 }
+
+/// An ownership over a position on the ring, which doesn't borrow from the
+/// ring.
+///
+/// Unlike [`RingToken`], holds a copy of the node, so it can be stored in
+/// structs, returned from async functions, or sent across channels. See
+/// [`RingToken::into_owned`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedRingToken<T> {
+    position: RingPosition,
+    node: T,
+}
+
+impl<T> OwnedRingToken<T> {
+    /// Return the position of the node on the ring.
+    pub const fn position(&self) -> RingPosition {
+        self.position
+    }
+
+    /// Return the node that owns this token.
+    pub const fn node(&self) -> &T {
+        &self.node
+    }
+
+    /// Converts the token into the node that owns it.
+    pub fn into_node(self) -> T {
+        self.node
+    }
+}
+
+impl<T: RingNode + Clone> From<RingToken<'_, T>> for OwnedRingToken<T> {
+    fn from(token: RingToken<'_, T>) -> Self {
+        token.into_owned()
+    }
+}
+
+impl<T> Deref for OwnedRingToken<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl<T> AsRef<T> for OwnedRingToken<T> {
+    fn as_ref(&self) -> &T {
+        &self.node
+    }
+}
+
+impl<T> Borrow<T> for OwnedRingToken<T> {
+    fn borrow(&self) -> &T {
+        &self.node
+    }
+}
+
+impl<T> PartialEq for OwnedRingToken<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+impl<T> Eq for OwnedRingToken<T> {}
+
+impl<T: PartialEq> PartialEq<T> for OwnedRingToken<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.node == *other
+    }
+}
+
+impl<T: PartialEq> PartialEq<&T> for OwnedRingToken<T> {
+    fn eq(&self, other: &&T) -> bool {
+        self.node == **other
+    }
+}
+
+impl<T: RingNode> PartialEq<RingToken<'_, T>> for OwnedRingToken<T> {
+    fn eq(&self, other: &RingToken<'_, T>) -> bool {
+        self.position == other.position()
+    }
+}
+
+impl<T> PartialOrd for OwnedRingToken<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for OwnedRingToken<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.position.cmp(&other.position)
+    }
+}
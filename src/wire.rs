@@ -0,0 +1,322 @@
+use {
+    crate::{HashRing, Partitioner, RingNode, RingState, TokenState, WireError},
+    xxhash_rust::xxh3::xxh3_64,
+};
+
+/// Magic bytes opening every encoded ring.
+const MAGIC: [u8; 4] = *b"MPCH";
+
+/// Current version of the wire format.
+pub const WIRE_FORMAT_VERSION: u8 = 1;
+
+/// Size of the trailing checksum.
+const CHECKSUM_LEN: usize = 8;
+
+/// Encodes and decodes nodes for the wire format.
+///
+/// See [`HashRing::encode`] for the format description.
+pub trait NodeCodec<N> {
+    /// Appends the encoded node to the buffer.
+    fn encode(&self, node: &N, buf: &mut Vec<u8>);
+
+    /// Decodes a node from its encoded bytes.
+    ///
+    /// Returns `None` if bytes don't represent a valid node.
+    fn decode(&self, bytes: &[u8]) -> Option<N>;
+}
+
+/// Partitioner which can be identified (and restored) in the wire format.
+pub trait WirePartitioner: Sized {
+    /// Identifier of the partitioner, unique among all the partitioners.
+    const ID: u8;
+
+    /// Appends the partitioner parameters (e.g. seeds) to the buffer.
+    fn encode_params(&self, buf: &mut Vec<u8>);
+
+    /// Restores the partitioner from its encoded parameters.
+    ///
+    /// Returns `None` if parameters are invalid.
+    fn decode_params(bytes: &[u8]) -> Option<Self>;
+}
+
+/// Codec for primitive integers (little-endian), strings (UTF-8) and byte
+/// vectors (as is).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawCodec;
+
+macro_rules! impl_raw_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl NodeCodec<$ty> for RawCodec {
+                fn encode(&self, node: &$ty, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&node.to_le_bytes());
+                }
+
+                fn decode(&self, bytes: &[u8]) -> Option<$ty> {
+                    bytes.try_into().ok().map(<$ty>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl NodeCodec<String> for RawCodec {
+    fn encode(&self, node: &String, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(node.as_bytes());
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<String> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl NodeCodec<Vec<u8>> for RawCodec {
+    fn encode(&self, node: &Vec<u8>, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(node);
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        Some(bytes.to_vec())
+    }
+}
+
+impl<N, P> HashRing<N, P>
+where
+    N: RingNode + Clone,
+    P: Partitioner<N> + WirePartitioner + Clone,
+{
+    /// Encodes the ring into a compact binary form.
+    ///
+    /// Use [`decode()`](Self::decode) to restore the ring.
+    ///
+    /// # Format
+    ///
+    /// All the integers marked as `varint` are unsigned LEB128.
+    ///
+    /// | Field            | Encoding                                   |
+    /// |------------------|--------------------------------------------|
+    /// | magic            | `b"MPCH"`                                  |
+    /// | version          | `u8`, currently `1`                        |
+    /// | flags            | `u8`, reserved, must be `0`                |
+    /// | probe count      | `varint`                                   |
+    /// | partitioner id   | `u8`, see [`WirePartitioner::ID`]          |
+    /// | partitioner size | `varint`                                   |
+    /// | partitioner      | [`WirePartitioner::encode_params`]         |
+    /// | epoch            | `varint`                                   |
+    /// | token count      | `varint`                                   |
+    /// | tokens           | token count times the token record         |
+    /// | checksum         | `u64` (LE), XXH3 of all the preceding bytes |
+    ///
+    /// Tokens are ordered by position, and each token record is:
+    ///
+    /// | Field     | Encoding                                              |
+    /// |-----------|-------------------------------------------------------|
+    /// | delta     | `varint`, position minus the previous one (or zero)   |
+    /// | weight    | `varint`, `weight << 1 \| inserted`                   |
+    /// | node size | `varint`                                              |
+    /// | node      | [`NodeCodec::encode`]                                 |
+    ///
+    /// Here, `inserted` is `1` if the node was placed with
+    /// [`insert()`](Self::insert) (see [`TokenState::inserted`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, RawCodec};
+    ///
+    /// let ring = HashRing::<u64>::new();
+    /// (0..5).for_each(|i| ring.add(i));
+    ///
+    /// let bytes = ring.encode(&RawCodec);
+    /// let restored = HashRing::<u64>::decode(&bytes, &RawCodec).expect("valid ring");
+    /// assert_eq!(restored.len(), 5);
+    /// assert_eq!(
+    ///     restored.node(&"key").map(|t| *t.node()),
+    ///     ring.node(&"key").map(|t| *t.node())
+    /// );
+    /// ```
+    pub fn encode<C: NodeCodec<N>>(&self, codec: &C) -> Vec<u8> {
+        let state = self.to_state();
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.push(WIRE_FORMAT_VERSION);
+        buf.push(0);
+        write_varint(&mut buf, state.probe_count as u64);
+
+        buf.push(P::ID);
+        let mut params = Vec::new();
+        state.partitioner.encode_params(&mut params);
+        write_bytes(&mut buf, &params);
+
+        write_varint(&mut buf, state.epoch);
+        write_varint(&mut buf, state.tokens.len() as u64);
+        let mut prev = 0;
+        let mut node = Vec::new();
+        for token in &state.tokens {
+            write_varint(&mut buf, token.position - prev);
+            write_varint(
+                &mut buf,
+                u64::from(token.weight) << 1 | u64::from(token.inserted),
+            );
+            node.clear();
+            codec.encode(&token.node, &mut node);
+            write_bytes(&mut buf, &node);
+            prev = token.position;
+        }
+
+        let checksum = xxh3_64(&buf);
+        buf.extend_from_slice(&checksum.to_le_bytes());
+        buf
+    }
+
+    /// Decodes the ring encoded with [`encode()`](Self::encode).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WireError`] if bytes are corrupted, were produced by an
+    /// unsupported version of the format or by a different partitioner, or
+    /// represent an invalid ring (see [`from_state()`](Self::from_state)).
+    pub fn decode<C: NodeCodec<N>>(bytes: &[u8], codec: &C) -> Result<Self, WireError> {
+        if bytes.len() < MAGIC.len() + 2 + CHECKSUM_LEN {
+            return Err(WireError::UnexpectedEof);
+        }
+        if bytes[..MAGIC.len()] != MAGIC {
+            return Err(WireError::InvalidMagic);
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        let checksum = u64::from_le_bytes(checksum.try_into().expect("checksum size"));
+        if xxh3_64(body) != checksum {
+            return Err(WireError::ChecksumMismatch);
+        }
+
+        let mut reader = Reader(&body[MAGIC.len()..]);
+        let version = reader.byte()?;
+        if version != WIRE_FORMAT_VERSION {
+            return Err(WireError::UnsupportedVersion(version));
+        }
+        let flags = reader.byte()?;
+        if flags != 0 {
+            return Err(WireError::UnsupportedFlags(flags));
+        }
+        let probe_count = reader.varint_usize()?;
+
+        let id = reader.byte()?;
+        if id != P::ID {
+            return Err(WireError::PartitionerMismatch(id));
+        }
+        let partitioner = P::decode_params(reader.bytes()?).ok_or(WireError::InvalidPartitioner)?;
+
+        let epoch = reader.varint()?;
+        let count = reader.varint_usize()?;
+        let mut tokens = Vec::with_capacity(count.min(reader.0.len()));
+        let mut position: u64 = 0;
+        for _ in 0..count {
+            position = position
+                .checked_add(reader.varint()?)
+                .ok_or(WireError::InvalidVarint)?;
+            let weight = reader.varint()?;
+            let node = codec
+                .decode(reader.bytes()?)
+                .ok_or(WireError::InvalidNode)?;
+            tokens.push(TokenState {
+                position,
+                node,
+                weight: u32::try_from(weight >> 1).map_err(|_| WireError::InvalidVarint)?,
+                inserted: weight & 1 == 1,
+            });
+        }
+        if !reader.0.is_empty() {
+            return Err(WireError::TrailingBytes);
+        }
+
+        Self::from_state(RingState {
+            probe_count,
+            partitioner,
+            epoch,
+            tokens,
+        })
+        .map_err(WireError::State)
+    }
+}
+
+/// Appends unsigned LEB128 encoded integer to the buffer.
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Appends length-prefixed bytes to the buffer.
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+/// Cursor over the encoded bytes.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, WireError> {
+        let (&byte, rest) = self.0.split_first().ok_or(WireError::UnexpectedEof)?;
+        self.0 = rest;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, WireError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+            if shift == 63 && bits > 1 {
+                return Err(WireError::InvalidVarint);
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(WireError::InvalidVarint)
+    }
+
+    fn varint_usize(&mut self) -> Result<usize, WireError> {
+        usize::try_from(self.varint()?).map_err(|_| WireError::InvalidVarint)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], WireError> {
+        let len = self.varint_usize()?;
+        if len > self.0.len() {
+            return Err(WireError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX - 1, u64::MAX] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            let mut reader = Reader(&buf);
+            assert_eq!(reader.varint(), Ok(value));
+            assert!(reader.0.is_empty());
+        }
+
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, [0xac, 0x02]);
+
+        // Overflowing and truncated values.
+        assert_eq!(Reader(&[0xff; 10]).varint(), Err(WireError::InvalidVarint));
+        assert_eq!(Reader(&[0x80]).varint(), Err(WireError::UnexpectedEof));
+    }
+}
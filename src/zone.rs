@@ -0,0 +1,106 @@
+use {
+    crate::{HashRing, Partitioner, RingDirection::Clockwise, RingNode, RingToken},
+    std::hash::Hash,
+};
+
+/// Node which belongs to some failure domain (e.g. availability zone or
+/// rack).
+///
+/// See [`HashRing::replicas_diverse`] for details.
+pub trait ZonedNode: RingNode {
+    /// Failure domain label.
+    type Zone: PartialEq;
+
+    /// Returns the failure domain of the node.
+    fn zone(&self) -> Self::Zone;
+}
+
+impl<N: RingNode, P: Partitioner<N>> HashRing<N, P> {
+    /// Returns `k` nodes responsible for the given key, spread over as many
+    /// failure domains as possible.
+    ///
+    /// As with [`replicas()`](Self::replicas), nodes are collected by moving
+    /// clockwise from the winning probe position of the key, and the first
+    /// node is the primary node for the key. However, nodes from already used
+    /// zones are skipped. If there are fewer zones than requested replicas,
+    /// the skipped nodes are used to fill in the remaining slots (in the
+    /// clockwise order).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mpchash::{HashRing, ZonedNode};
+    ///
+    /// #[derive(Hash, PartialEq)]
+    /// struct Node {
+    ///     id: u64,
+    ///     zone: &'static str,
+    /// }
+    ///
+    /// impl ZonedNode for Node {
+    ///     type Zone = &'static str;
+    ///
+    ///     fn zone(&self) -> Self::Zone {
+    ///         self.zone
+    ///     }
+    /// }
+    ///
+    /// let ring = HashRing::new();
+    /// for id in 0..9 {
+    ///     let zone = ["us-east-1a", "us-east-1b", "us-east-1c"][id as usize % 3];
+    ///     ring.add(Node { id, zone });
+    /// }
+    ///
+    /// let replicas = ring.replicas_diverse(&"key", 3);
+    /// assert_eq!(replicas.len(), 3);
+    /// assert_ne!(replicas[0].zone, replicas[1].zone);
+    /// assert_ne!(replicas[0].zone, replicas[2].zone);
+    /// assert_ne!(replicas[1].zone, replicas[2].zone);
+    /// ```
+    pub fn replicas_diverse<K: Hash>(&self, key: &K, k: usize) -> Vec<RingToken<'_, N>>
+    where
+        N: ZonedNode,
+        P: Partitioner<K>,
+    {
+        self.replicas_diverse_by(key, k, N::zone)
+    }
+
+    /// Returns `k` nodes responsible for the given key, spread over as many
+    /// failure domains (as returned by `zone`) as possible.
+    ///
+    /// Allows to keep the zones in a side table, instead of implementing
+    /// [`ZonedNode`]. See [`replicas_diverse()`](Self::replicas_diverse) for
+    /// details.
+    pub fn replicas_diverse_by<K, Z, F>(&self, key: &K, k: usize, zone: F) -> Vec<RingToken<'_, N>>
+    where
+        K: Hash,
+        P: Partitioner<K>,
+        Z: PartialEq,
+        F: Fn(&N) -> Z,
+    {
+        let Some((_, primary)) = self.primary_probe(key) else {
+            return Vec::new();
+        };
+
+        let mut replicas = Vec::with_capacity(k);
+        let mut zones = Vec::with_capacity(k);
+        let mut skipped = Vec::new();
+        for token in self.tokens(primary.position(), Clockwise) {
+            if replicas.len() == k {
+                break;
+            }
+            let token_zone = zone(token.node());
+            if zones.contains(&token_zone) {
+                skipped.push(token);
+            } else {
+                zones.push(token_zone);
+                replicas.push(token);
+            }
+        }
+
+        // Zones are exhausted, fall back to the skipped nodes.
+        let missing = k - replicas.len();
+        replicas.extend(skipped.into_iter().take(missing));
+        replicas
+    }
+}
@@ -0,0 +1,95 @@
+use {
+    mpchash::{HashRing, HashRingBuilder, SortedArrayStorage},
+    rand::random,
+};
+
+fn random_ring(nodes: usize, probe_count: usize) -> HashRing<u64> {
+    let ring = HashRingBuilder::new().probe_count(probe_count).build();
+    for _ in 0..nodes {
+        ring.add_weighted(random(), 1 + random::<u32>() % 3);
+    }
+    ring
+}
+
+#[test]
+fn node_batch_matches_node() {
+    for probe_count in [1, 2, 23] {
+        for nodes in [1, 2, 50] {
+            let ring = random_ring(nodes, probe_count);
+            let keys = (0..500).map(|_| random::<u64>()).collect::<Vec<_>>();
+            let batch = ring.node_batch(&keys);
+            assert_eq!(batch.len(), keys.len());
+            for (key, token) in keys.iter().zip(batch) {
+                assert_eq!(token, ring.node(key));
+            }
+        }
+    }
+
+    // Keys of other types, duplicate keys.
+    let ring = random_ring(10, 23);
+    let keys = ["a", "b", "a", "c"];
+    let batch = ring.node_batch(&keys);
+    for (key, token) in keys.iter().zip(batch) {
+        assert_eq!(token, ring.node(key));
+    }
+}
+
+#[test]
+fn node_batch_sorted_array() {
+    let ring = HashRingBuilder::new().storage(SortedArrayStorage).build();
+    for _ in 0..100 {
+        ring.add_weighted(random::<u64>(), 1 + random::<u32>() % 3);
+    }
+    for size in [1, 16, 1000] {
+        let keys = (0..size).map(|_| random::<u64>()).collect::<Vec<_>>();
+        for (key, token) in keys.iter().zip(ring.node_batch(&keys)) {
+            assert_eq!(token, ring.node(key));
+        }
+    }
+}
+
+#[test]
+fn node_batch_wraps_around() {
+    // Probes beyond the last node are owned by the first one.
+    let ring = HashRing::new();
+    ring.insert(u64::MAX / 3, 1u64);
+    ring.insert(u64::MAX / 2, 2);
+    let keys = (0..200u64).collect::<Vec<_>>();
+    for (key, token) in keys.iter().zip(ring.node_batch(&keys)) {
+        assert_eq!(token, ring.node(key));
+    }
+}
+
+#[test]
+fn node_batch_empty() {
+    let ring = HashRing::<u64>::new();
+    assert_eq!(ring.node_batch(&[1u64, 2, 3]), vec![None, None, None]);
+    assert!(ring.group_by_node(&[1u64, 2, 3]).is_empty());
+
+    let ring = random_ring(10, 23);
+    assert!(ring.node_batch::<u64>(&[]).is_empty());
+    assert!(ring.group_by_node::<u64>(&[]).is_empty());
+}
+
+#[test]
+fn group_by_node() {
+    let ring = random_ring(20, 23);
+    let keys = (0..1000u64).collect::<Vec<_>>();
+    let groups = ring.group_by_node(&keys);
+
+    // Groups are ordered by positions, and cover all the keys.
+    assert!(groups
+        .windows(2)
+        .all(|w| w[0].0.position() < w[1].0.position()));
+    assert_eq!(
+        groups.iter().map(|(_, keys)| keys.len()).sum::<usize>(),
+        1000
+    );
+    for (token, keys) in &groups {
+        assert!(!keys.is_empty());
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        for key in keys {
+            assert_eq!(ring.node(*key).as_ref(), Some(token));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use {
+    mpchash::{BoundedLoadRing, HashRing},
+    rand::random,
+};
+
+#[derive(Hash, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Node {
+    id: u64,
+}
+
+fn ring(num_nodes: u64) -> HashRing<Node> {
+    let ring = HashRing::new();
+    (0..num_nodes).for_each(|id| ring.add(Node { id }));
+    ring
+}
+
+#[test]
+fn empty_ring() {
+    let bounded = BoundedLoadRing::new(HashRing::<Node>::new(), 1.25);
+    assert!(bounded.assign(&"key").is_none());
+    assert!(!bounded.release(&"key"));
+    assert_eq!(bounded.total_load(), 0);
+}
+
+#[test]
+fn loads_are_bounded() {
+    let bounded = BoundedLoadRing::new(ring(10), 1.25);
+    for _ in 0..10_000 {
+        bounded.assign(&random::<u64>()).expect("non-empty ring");
+    }
+    assert_eq!(bounded.total_load(), 10_000);
+
+    // Capacity is `ceil(1.25 * 10_000 / 10)`.
+    let capacity = 1250;
+    let loads = bounded.loads();
+    assert_eq!(loads.len(), 10);
+    assert_eq!(loads.iter().map(|(_, load)| load).sum::<usize>(), 10_000);
+    for (token, load) in loads {
+        assert!(load <= capacity);
+        assert_eq!(bounded.load(&token), load);
+    }
+}
+
+#[test]
+fn hot_key_is_spread() {
+    let bounded = BoundedLoadRing::new(ring(10), 1.0);
+    let primary = *bounded.ring().node(&"hot").expect("non-empty ring");
+
+    // Until primary node is at capacity, it is selected.
+    let token = bounded.assign(&"hot").expect("non-empty ring");
+    assert_eq!(token, primary);
+
+    // With `c = 1.0`, keys are spread evenly over all the nodes.
+    for _ in 1..100 {
+        bounded.assign(&"hot").expect("non-empty ring");
+    }
+    for (_, load) in bounded.loads() {
+        assert_eq!(load, 10);
+    }
+    assert_eq!(bounded.capacity(&primary), 11);
+
+    // Release all the assignments.
+    for _ in 0..100 {
+        assert!(bounded.release(&"hot"));
+    }
+    assert!(!bounded.release(&"hot"));
+    assert_eq!(bounded.total_load(), 0);
+    assert_eq!(bounded.load(&primary), 0);
+}
+
+#[test]
+fn release() {
+    let bounded = BoundedLoadRing::new(ring(5), 1.25);
+    let token = bounded.assign(&1).expect("non-empty ring");
+    let node = *token;
+    bounded.assign(&2).expect("non-empty ring");
+    assert_eq!(bounded.total_load(), 2);
+
+    // Releasing a key, which is not assigned, has no effect.
+    assert!(!bounded.release(&3));
+    assert_eq!(bounded.total_load(), 2);
+
+    assert!(bounded.release(&1));
+    assert!(!bounded.release(&1));
+    assert_eq!(bounded.total_load(), 1);
+    assert!(bounded.load(&node) <= 1);
+}
+
+#[test]
+fn load_of_inserted_nodes() {
+    let ring = HashRing::new();
+    ring.insert(u64::MAX / 3, Node { id: 1 });
+    ring.insert(u64::MAX / 3 * 2, Node { id: 2 });
+    ring.insert(u64::MAX, Node { id: 2 });
+
+    let bounded = BoundedLoadRing::new(ring, 1.25);
+    for key in 0..100u64 {
+        bounded.assign(&key).expect("non-empty ring");
+    }
+
+    // Loads of all the positions of a node are summed up.
+    let loads = bounded.loads();
+    for id in [1, 2] {
+        let expected = loads
+            .iter()
+            .filter(|(token, _)| token.node().id == id)
+            .map(|(_, load)| load)
+            .sum::<usize>();
+        assert!(expected > 0);
+        assert_eq!(bounded.load(&Node { id }), expected);
+    }
+}
+
+#[test]
+fn capacity_is_proportional_to_weight() {
+    let ring = HashRing::new();
+    ring.add_weighted(Node { id: 1 }, 1);
+    ring.add_weighted(Node { id: 2 }, 4);
+
+    // With `c = 1.0`, capacities sum up to the total load, so the loads match
+    // the weight shares exactly.
+    let bounded = BoundedLoadRing::new(ring, 1.0);
+    for _ in 0..100 {
+        bounded.assign(&random::<u64>()).expect("non-empty ring");
+    }
+    assert_eq!(bounded.load(&Node { id: 1 }), 20);
+    assert_eq!(bounded.load(&Node { id: 2 }), 80);
+    assert_eq!(bounded.capacity(&Node { id: 1 }), 21);
+    assert_eq!(bounded.capacity(&Node { id: 2 }), 81);
+    assert_eq!(bounded.capacity(&Node { id: 3 }), 0);
+}
+
+#[test]
+fn load_of_removed_nodes_is_dropped() {
+    let ring = ring(5);
+    let bounded = BoundedLoadRing::new(ring.clone(), 1.25);
+    let keys = (0..1000u64).collect::<Vec<_>>();
+    for key in &keys {
+        bounded.assign(key).expect("non-empty ring");
+    }
+
+    let node = Node { id: 0 };
+    let load = bounded.load(&node);
+    assert!(load > 0);
+    ring.remove(&node);
+    assert_eq!(bounded.total_load(), keys.len() - load);
+    assert_eq!(
+        bounded.capacity(&Node { id: 1 }),
+        ((keys.len() - load + 1) as f64 * 1.25 / 4.0).ceil() as usize
+    );
+
+    // Assignments to the removed node are dropped as well.
+    let released = keys.iter().filter(|&key| bounded.release(key)).count();
+    assert_eq!(released, keys.len() - load);
+    assert_eq!(bounded.total_load(), 0);
+    assert!(bounded.loads().iter().all(|(_, load)| *load == 0));
+}
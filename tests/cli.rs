@@ -0,0 +1,148 @@
+#![cfg(feature = "cli")]
+
+use {
+    mpchash::HashRing,
+    std::{fs, path::PathBuf, process::Command},
+};
+
+fn ring_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mpchash-cli-{}-{name}", std::process::id()));
+    fs::write(&path, contents).expect("write ring file");
+    path
+}
+
+fn mpchash(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_mpchash"))
+        .args(args)
+        .output()
+        .expect("run mpchash");
+    let stdout = String::from_utf8(output.stdout).expect("utf-8 output");
+    (output.status.success(), stdout)
+}
+
+const RING: &str = r#"
+[[nodes]]
+name = "cache-1"
+
+[[nodes]]
+name = "cache-2"
+weight = 2
+
+[[nodes]]
+name = "cache-3"
+"#;
+
+fn library_ring() -> HashRing<String> {
+    let ring = HashRing::new();
+    ring.add("cache-1".to_string());
+    ring.add_weighted("cache-2".to_string(), 2);
+    ring.add("cache-3".to_string());
+    ring
+}
+
+#[test]
+fn lookup_and_replicas() {
+    let path = ring_file("lookup.toml", RING);
+    let path = path.to_str().expect("utf-8 path");
+    let ring = library_ring();
+
+    for key in ["user:1", "user:2", "user:123"] {
+        let (ok, out) = mpchash(&["lookup", "-r", path, key]);
+        assert!(ok);
+        let expected = ring.node(&key.to_string()).expect("non-empty ring");
+        assert!(out.starts_with(&format!("node:     {}\n", expected.node())));
+
+        let (ok, out) = mpchash(&["replicas", "-r", path, key, "-n", "2"]);
+        assert!(ok);
+        let expected = ring
+            .replicas(&key, 2)
+            .iter()
+            .map(|token| format!("{}\t{}\n", token.position(), token.node()))
+            .collect::<String>();
+        assert_eq!(out, expected);
+    }
+
+    // Integer keys are hashed as integers.
+    let (ok, out) = mpchash(&["lookup", "-r", path, "-t", "u64", "123"]);
+    assert!(ok);
+    let expected = ring.node(&123u64).expect("non-empty ring");
+    assert!(out.starts_with(&format!("node:     {}\n", expected.node())));
+}
+
+#[test]
+fn intervals_and_ranges() {
+    let path = ring_file("ranges.toml", RING);
+    let path = path.to_str().expect("utf-8 path");
+    let ring = library_ring();
+
+    let (ok, out) = mpchash(&["intervals", "-r", path, "cache-2"]);
+    assert!(ok);
+    let range = &ring.intervals(&"cache-2".to_string()).expect("node exists")[0];
+    assert_eq!(out, format!("[{}, {})\n", range.start, range.end));
+
+    let (ok, out) = mpchash(&["ranges", "-r", path]);
+    assert!(ok);
+    assert_eq!(out.lines().count(), 3);
+
+    let (ok, _) = mpchash(&["intervals", "-r", path, "cache-9"]);
+    assert!(!ok);
+
+    // Nodes placed at explicit positions have intervals too.
+    let path = ring_file(
+        "positioned.toml",
+        &format!("{RING}\n[[nodes]]\nname = \"cache-4\"\nposition = 42\n"),
+    );
+    let (ok, out) = mpchash(&[
+        "intervals",
+        "-r",
+        path.to_str().expect("utf-8 path"),
+        "cache-4",
+    ]);
+    assert!(ok);
+    assert!(out.ends_with(", 42)\n"));
+}
+
+#[test]
+fn diff() {
+    let old = ring_file("old.toml", RING);
+    let new = ring_file(
+        "new.toml",
+        r#"
+        probe_count = 23
+
+        [[nodes]]
+        name = "cache-1"
+
+        [[nodes]]
+        name = "cache-2"
+        weight = 2
+        "#,
+    );
+    let (ok, out) = mpchash(&[
+        "diff",
+        old.to_str().expect("utf-8 path"),
+        new.to_str().expect("utf-8 path"),
+    ]);
+    assert!(ok);
+    let lines = out.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("- cache-3\t"));
+    let changes = &lines[1..];
+    assert!(changes.iter().all(|line| line.starts_with("~ ")));
+    assert!(changes.iter().any(|line| line.contains("cache-3 -> ")));
+    assert!(changes.iter().all(|line| !line.contains("-> cache-3")));
+
+    let ring = library_ring();
+    ring.remove(&"cache-3".to_string());
+    assert_eq!(changes.len(), library_ring().diff(&ring, 1).changes.len());
+}
+
+#[test]
+fn invalid_files() {
+    let path = ring_file("invalid.toml", "probe_count = 0");
+    let (ok, _) = mpchash(&["ranges", "-r", path.to_str().expect("utf-8 path")]);
+    assert!(!ok);
+
+    let path = ring_file("unknown.toml", "[[nodes]]\nname = \"a\"\nzone = 1");
+    let (ok, _) = mpchash(&["ranges", "-r", path.to_str().expect("utf-8 path")]);
+    assert!(!ok);
+}
@@ -0,0 +1,159 @@
+use {mpchash::HashRing, rand::random};
+
+#[derive(Hash, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Node {
+    id: u64,
+}
+
+impl Node {
+    fn random() -> Self {
+        Self { id: random() }
+    }
+}
+
+/// Checks that for every sampled key, exactly the changes matching its actual
+/// movement are listed.
+#[track_caller]
+fn assert_diff(old: &HashRing<Node>, new: &HashRing<Node>, r: usize) {
+    let diff = old.diff(new, r);
+    for key in 0..2_000u64 {
+        let old_replicas = old
+            .replicas(&key, r)
+            .iter()
+            .map(|t| *t.node())
+            .collect::<Vec<_>>();
+        let new_replicas = new
+            .replicas(&key, r)
+            .iter()
+            .map(|t| *t.node())
+            .collect::<Vec<_>>();
+        let Some(probe) = new.probe_position(&key) else {
+            continue;
+        };
+        let matching = diff
+            .changes
+            .iter()
+            .filter(|change| change.range.contains(&probe) || change.range.covers_whole_ring())
+            .filter(|change| change.old_replicas == old_replicas)
+            .collect::<Vec<_>>();
+        if old_replicas == new_replicas || old.is_empty() {
+            assert!(matching.is_empty(), "key {key} is not moved");
+        } else {
+            assert_eq!(matching.len(), 1, "key {key} is moved");
+            assert_eq!(matching[0].new_replicas, new_replicas);
+        }
+    }
+}
+
+#[test]
+fn identical_rings() {
+    let ring = HashRing::new();
+    (0..10).for_each(|_| ring.add(Node::random()));
+    assert!(ring.diff(&ring, 3).is_empty());
+    assert!(HashRing::<Node>::new().diff(&HashRing::new(), 3).is_empty());
+}
+
+#[test]
+fn added_and_removed() {
+    let old = HashRing::new();
+    let new = HashRing::new();
+    let common = (0..10).map(|_| Node::random()).collect::<Vec<_>>();
+    common.iter().for_each(|node| {
+        old.add(*node);
+        new.add(*node);
+    });
+    let node1 = Node::random();
+    let node2 = Node::random();
+    old.add(node1);
+    new.add(node2);
+
+    let diff = old.diff(&new, 2);
+    assert_eq!(diff.added, vec![(new.position(&node2), node2)]);
+    assert_eq!(diff.removed, vec![(old.position(&node1), node1)]);
+    assert!(!diff.changes.is_empty());
+
+    // Same position, different node.
+    let old = HashRing::new();
+    let new = HashRing::new();
+    old.insert(10, node1);
+    new.insert(10, node2);
+    let diff = old.diff(&new, 1);
+    assert_eq!(diff.added, vec![(10, node2)]);
+    assert_eq!(diff.removed, vec![(10, node1)]);
+    assert_eq!(diff.changes.len(), 1);
+    assert!(diff.changes[0].range.covers_whole_ring());
+    assert_eq!(diff.changes[0].old_primary(), Some(&node1));
+    assert_eq!(diff.changes[0].new_primary(), Some(&node2));
+}
+
+#[test]
+fn changed_regions() {
+    for weighted in [false, true] {
+        for r in [1, 3] {
+            for _ in 0..5 {
+                let old = HashRing::new();
+                let new = HashRing::new();
+                for _ in 0..20 {
+                    let node = Node::random();
+                    let weight = if weighted { 1 + random::<u32>() % 4 } else { 1 };
+                    match random::<u8>() % 5 {
+                        0 => old.add_weighted(node, weight),
+                        1 => new.add_weighted(node, weight),
+                        2 if weighted => {
+                            old.add_weighted(node, weight);
+                            new.add_weighted(node, weight + 1);
+                        }
+                        _ => {
+                            old.add_weighted(node, weight);
+                            new.add_weighted(node, weight);
+                        }
+                    }
+                }
+                assert_diff(&old, &new, r);
+            }
+        }
+    }
+}
+
+#[test]
+fn added_node_captures_keys_of_all_nodes() {
+    let old = HashRing::new();
+    let new = HashRing::new();
+    for id in 0..10 {
+        old.add(Node { id });
+        new.add(Node { id });
+    }
+    new.add(Node { id: 10 });
+
+    let diff = old.diff(&new, 1);
+    assert_eq!(diff.added, vec![(new.position(&Node { id: 10 }), Node {
+        id: 10
+    })]);
+    for id in 0..10 {
+        assert!(diff
+            .changes
+            .iter()
+            .any(|change| change.old_primary() == Some(&Node { id })));
+    }
+    assert_diff(&old, &new, 1);
+    assert_diff(&new, &old, 1);
+    assert_diff(&old, &new, 3);
+}
+
+#[test]
+fn inserted_nodes() {
+    let old = HashRing::new();
+    (0..10).for_each(|_| old.add(Node::random()));
+    let new = HashRing::new();
+    old.nodes()
+        .for_each(|token| new.insert(token.position(), *token.node()));
+
+    // Node at several positions.
+    let node = Node::random();
+    new.insert(random(), node);
+    new.insert(random(), node);
+    for r in [1, 3] {
+        assert_diff(&old, &new, r);
+        assert_diff(&new, &old, r);
+    }
+}
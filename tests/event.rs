@@ -0,0 +1,258 @@
+use {
+    mpchash::{HashRing, KeyRange, RingEvent},
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    },
+};
+
+#[test]
+fn added_and_removed() {
+    let ring = HashRing::<u64>::new();
+    let events = ring.subscribe();
+
+    ring.insert(10, 1);
+    ring.insert(20, 2);
+    ring.insert(30, 3);
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![
+        RingEvent::NodeAdded {
+            node: 1,
+            position: 10,
+            range: KeyRange::new(10, 10),
+            epoch: 1,
+        },
+        RingEvent::NodeAdded {
+            node: 2,
+            position: 20,
+            range: KeyRange::new(10, 20),
+            epoch: 2,
+        },
+        RingEvent::NodeAdded {
+            node: 3,
+            position: 30,
+            range: KeyRange::new(20, 30),
+            epoch: 3,
+        },
+    ]);
+
+    ring.add(4);
+    let added = events.recv().expect("event");
+    let intervals = ring.intervals(&4).expect("node exists");
+    assert!(matches!(added, RingEvent::NodeAdded {
+        node: 4,
+        epoch: 4,
+        ..
+    }));
+    assert_eq!(vec![added.range().clone()], intervals);
+    assert_eq!(
+        ring.key_range(added.position()),
+        Some(added.range().clone())
+    );
+
+    ring.remove(&4);
+    let removed = events.recv().expect("event");
+    assert!(matches!(removed, RingEvent::NodeRemoved {
+        node: 4,
+        epoch: 5,
+        ..
+    }));
+    assert_eq!(vec![removed.range().clone()], intervals);
+    assert_eq!(removed.position(), added.position());
+}
+
+#[test]
+fn removed_at_and_cleared() {
+    let ring = HashRing::<u64>::new();
+    ring.insert(10, 1);
+    ring.insert(20, 2);
+    ring.insert(30, 3);
+    let events = ring.subscribe();
+
+    ring.remove_at(20);
+    assert!(ring.remove_at(20).is_none());
+    assert_eq!(ring.clear(), 2);
+    assert_eq!(ring.clear(), 0);
+    assert!(ring.is_empty());
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![
+        RingEvent::NodeRemoved {
+            node: 2,
+            position: 20,
+            range: KeyRange::new(10, 20),
+            epoch: 4,
+        },
+        RingEvent::NodeRemoved {
+            node: 1,
+            position: 10,
+            range: KeyRange::new(30, 10),
+            epoch: 5,
+        },
+        RingEvent::NodeRemoved {
+            node: 3,
+            position: 30,
+            range: KeyRange::new(30, 30),
+            epoch: 6,
+        },
+    ]);
+}
+
+#[test]
+fn removed_concurrently() {
+    let ring = HashRing::<u64>::new();
+    (0..100).for_each(|node| ring.add(node));
+    let epoch = ring.epoch();
+    let events = ring.subscribe();
+
+    // Every node is removed by several threads, but only once.
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| (0..100).for_each(|node| ring.remove(&node)));
+        }
+    });
+    assert!(ring.is_empty());
+    assert_eq!(ring.epoch(), epoch + 100);
+    assert_eq!(events.try_iter().count(), 100);
+}
+
+#[test]
+fn removed_at_concurrently() {
+    let ring = HashRing::<u64>::new();
+    (0..100).for_each(|node| ring.insert(node, node));
+
+    // Every node is reported as removed by a single thread.
+    let removed = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for thread in 0..4 {
+            let (ring, removed) = (&ring, &removed);
+            scope.spawn(move || {
+                let count = if thread % 2 == 0 {
+                    (0..100).filter_map(|pos| ring.remove_at(pos)).count()
+                } else {
+                    ring.clear()
+                };
+                removed.fetch_add(count, Ordering::SeqCst);
+            });
+        }
+    });
+    assert!(ring.is_empty());
+    assert_eq!(removed.load(Ordering::SeqCst), 100);
+}
+
+#[test]
+fn replaced_node() {
+    let ring = HashRing::<u64>::new();
+    ring.insert(10, 1);
+    ring.insert(20, 2);
+    let events = ring.subscribe();
+
+    // Node at the same position is replaced.
+    ring.insert(20, 3);
+    let events = events.try_iter().collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], RingEvent::NodeRemoved {
+        node: 2,
+        position: 20,
+        epoch: 3,
+        ..
+    }));
+    assert!(matches!(events[1], RingEvent::NodeAdded {
+        node: 3,
+        position: 20,
+        epoch: 3,
+        ..
+    }));
+    assert_eq!(events[0].range(), events[1].range());
+}
+
+#[test]
+fn replaced_concurrently() {
+    let ring = HashRing::<u64>::new();
+    let events = ring.subscribe();
+
+    // Every replaced node is reported exactly once.
+    thread::scope(|scope| {
+        for thread in 0..4 {
+            let ring = &ring;
+            scope.spawn(move || (0..100).for_each(|node| ring.insert(10, thread * 100 + node)));
+        }
+    });
+    assert_eq!(ring.len(), 1);
+    let events = events.try_iter().collect::<Vec<_>>();
+    let added = events
+        .iter()
+        .filter(|event| matches!(event, RingEvent::NodeAdded { .. }));
+    assert_eq!(added.count(), 400);
+    assert_eq!(events.len(), 2 * 400 - 1);
+}
+
+#[test]
+fn no_events() {
+    let ring = HashRing::<u64>::new();
+    ring.add(1);
+    let events = ring.subscribe();
+
+    // Weight changes and missing nodes are not membership changes.
+    ring.add_weighted(1, 3);
+    ring.add(1);
+    ring.remove(&2);
+    assert!(events.try_recv().is_err());
+
+    // Detached copies don't notify subscribers of the original ring.
+    let plan = ring.plan_add(&2, 1);
+    assert_eq!(plan.target().len(), 2);
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn clones_share_subscribers() {
+    let ring = HashRing::<u64>::new();
+    let events = ring.subscribe();
+
+    let clone = ring.clone();
+    clone.add(1);
+    assert_eq!(events.recv().expect("event").node(), &1);
+
+    // Subscribers of the clone are notified on changes of the original ring.
+    let clone_events = clone.subscribe();
+    ring.add(2);
+    assert_eq!(events.recv().expect("event").node(), &2);
+    assert_eq!(clone_events.recv().expect("event").node(), &2);
+}
+
+#[test]
+fn dropped_receiver() {
+    let ring = HashRing::<u64>::new();
+    let events = ring.subscribe();
+    drop(events);
+
+    // Dropped subscriber is pruned on the next change.
+    ring.add(1);
+    ring.add(2);
+    assert_eq!(ring.len(), 2);
+}
+
+#[test]
+fn callback_modifies_ring() {
+    let ring = HashRing::<u64>::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    // Replace every removed node with a standby one.
+    let clone = ring.clone();
+    let counter = calls.clone();
+    ring.on_change(move |event| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        if let RingEvent::NodeRemoved { node, .. } = event {
+            clone.add(**node + 100);
+            assert!(!clone.snapshot().is_empty());
+        }
+    });
+
+    ring.add(1);
+    ring.add(2);
+    ring.remove(&1);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert_eq!(ring.len(), 2);
+    assert_eq!(ring.weight(&101), Some(1));
+}
@@ -0,0 +1,161 @@
+use {
+    mpchash::{AnchorHash, ConsistentHasher, HashRing, JumpHash, MaglevTable, RendezvousHash},
+    std::collections::{HashMap, HashSet},
+};
+
+const NODES: u64 = 10;
+const KEYS: u64 = 5000;
+
+/// How much keys move when a node is removed.
+#[derive(Clone, Copy, PartialEq)]
+enum Disruption {
+    /// Only the keys of the removed node move.
+    Minimal,
+    /// The keys of the last node move too.
+    LastNode,
+    /// A few keys of the other nodes move too.
+    Small,
+}
+
+fn owners(hasher: &dyn ConsistentHasher<u64, Node = u64>) -> Vec<u64> {
+    (0..KEYS).map(|key| hasher.node(&key).unwrap()).collect()
+}
+
+fn check_hasher(mut hasher: Box<dyn ConsistentHasher<u64, Node = u64>>, disruption: Disruption) {
+    assert!(hasher.is_empty());
+    assert_eq!(hasher.node(&1), None);
+    assert!(hasher.replicas(&1, 3).is_empty());
+    hasher.remove(&1);
+
+    (0..NODES).for_each(|node| hasher.add(node));
+    hasher.add(3);
+    assert_eq!(hasher.len(), NODES as usize);
+
+    // Replicas are distinct, and start at the primary node.
+    for key in 0..KEYS / 10 {
+        for k in [1, 3, 20] {
+            let replicas = hasher.replicas(&key, k);
+            assert_eq!(replicas.len(), k.min(NODES as usize));
+            assert_eq!(replicas[0], hasher.node(&key).unwrap());
+            assert_eq!(
+                replicas.iter().collect::<HashSet<_>>().len(),
+                replicas.len()
+            );
+        }
+    }
+
+    // Keys are spread over all the nodes.
+    let before = owners(&*hasher);
+    let mut load = HashMap::<u64, u64>::new();
+    before
+        .iter()
+        .for_each(|node| *load.entry(*node).or_default() += 1);
+    assert_eq!(load.len(), NODES as usize);
+    let mean = KEYS / NODES;
+    assert!(load
+        .values()
+        .all(|keys| *keys > mean / 2 && *keys < mean * 3 / 2));
+
+    // Keys of the removed node move to other nodes.
+    hasher.remove(&4);
+    assert_eq!(hasher.len(), NODES as usize - 1);
+    let after = owners(&*hasher);
+    let mut moved = 0;
+    for (old, new) in before.iter().zip(&after) {
+        assert_ne!(*new, 4);
+        if *old != 4 && old != new {
+            moved += 1;
+            match disruption {
+                Disruption::Minimal => panic!("key moved from {old} to {new}"),
+                Disruption::LastNode => assert_eq!(*old, NODES - 1),
+                Disruption::Small => {}
+            }
+        }
+    }
+    assert!(moved < KEYS / 10);
+
+    // Re-adding the node restores the ownership.
+    hasher.add(4);
+    if disruption == Disruption::Minimal {
+        assert_eq!(owners(&*hasher), before);
+    }
+}
+
+#[test]
+fn hash_ring() {
+    check_hasher(Box::new(HashRing::new()), Disruption::Minimal);
+}
+
+#[test]
+fn jump_hash() {
+    check_hasher(Box::new(JumpHash::new()), Disruption::LastNode);
+}
+
+#[test]
+fn rendezvous_hash() {
+    check_hasher(Box::new(RendezvousHash::new()), Disruption::Minimal);
+}
+
+#[test]
+fn maglev_table() {
+    check_hasher(Box::new(MaglevTable::new()), Disruption::Small);
+    check_hasher(
+        Box::new(MaglevTable::with_table_size(1009)),
+        Disruption::Small,
+    );
+}
+
+#[test]
+fn anchor_hash() {
+    check_hasher(Box::new(AnchorHash::new(10)), Disruption::Minimal);
+    check_hasher(Box::new(AnchorHash::new(1000)), Disruption::Minimal);
+}
+
+#[test]
+fn anchor_hash_removals() {
+    // Remove and re-add nodes in different orders: only the keys of the
+    // affected nodes move.
+    let mut anchor = AnchorHash::new(20);
+    (0..NODES).for_each(|node| anchor.add(node));
+    let mut current = owners(&anchor);
+    for node in [7, 2, 9, 0] {
+        anchor.remove(&node);
+        let next = owners(&anchor);
+        for (old, new) in current.iter().zip(&next) {
+            assert!(old == new || *old == node);
+        }
+        current = next;
+    }
+    for node in [100, 101] {
+        anchor.add(node);
+        let next = owners(&anchor);
+        for (old, new) in current.iter().zip(&next) {
+            assert!(old == new || *new == node);
+        }
+        current = next;
+    }
+    assert_eq!(anchor.len(), 8);
+    assert_eq!(anchor.capacity(), 20);
+
+    // All the nodes removed.
+    for node in [1, 3, 4, 5, 6, 8, 100, 101] {
+        anchor.remove(&node);
+    }
+    assert!(anchor.is_empty());
+    assert_eq!(anchor.node(&1), None);
+    anchor.add(42);
+    assert_eq!(anchor.node(&1), Some(&42));
+}
+
+#[test]
+#[should_panic(expected = "anchor is full")]
+fn anchor_hash_full() {
+    let mut anchor = AnchorHash::new(2);
+    (0..3u64).for_each(|node| anchor.add(node));
+}
+
+#[test]
+#[should_panic(expected = "prime")]
+fn maglev_table_size() {
+    let _ = MaglevTable::<u64>::with_table_size(1000);
+}
@@ -1,4 +1,24 @@
-use {mpchash::HashRing, rand::random, std::ops::Deref};
+use {
+    mpchash::{
+        HashRing,
+        HashRingBuilder,
+        Partitioner,
+        RingDirection,
+        RingPosition,
+        RingStorage,
+        SkipMapStorage,
+        SortedArrayStorage,
+        DEFAULT_SEED1,
+        DEFAULT_SEED2,
+    },
+    rand::random,
+    std::{
+        collections::{BTreeMap, HashMap},
+        hash::{DefaultHasher, Hash, Hasher},
+        ops::Deref,
+        thread,
+    },
+};
 
 #[derive(Hash, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 struct Node {
@@ -59,7 +79,7 @@ fn duplicate_peer() {
 
 #[test]
 fn walkthrough() {
-    // Anything that implements `Hash + Send` can be used as a node.
+    // Anything that implements `Hash + PartialEq + Send` can be used as a node.
     // Other traits used here are derived for testing purposes.
     #[derive(Hash, Debug, PartialEq, Clone, Copy)]
     struct MyNode(u64);
@@ -86,18 +106,18 @@ fn walkthrough() {
     //
     // Assuming a replication factor of 3, we can do:
     let tokens = ring.replicas(&key, 3);
-    assert_eq!(tokens, vec![&MyNode(1), &MyNode(2), &MyNode(3)]);
+    assert_eq!(tokens, vec![&MyNode(2), &MyNode(3), &MyNode(4)]);
 
     let tokens = ring.replicas(&key, 3);
     assert_eq!(tokens.iter().map(|e| e.node()).collect::<Vec<_>>(), vec![
-        &MyNode(1),
         &MyNode(2),
-        &MyNode(3)
+        &MyNode(3),
+        &MyNode(4)
     ]);
     assert_eq!(tokens.iter().map(Deref::deref).collect::<Vec<_>>(), vec![
-        &MyNode(1),
         &MyNode(2),
-        &MyNode(3)
+        &MyNode(3),
+        &MyNode(4)
     ]);
 
     // Before node removal we probably need to move its data.
@@ -118,5 +138,565 @@ fn walkthrough() {
     assert_eq!(token.node(), &MyNode(4));
 
     let tokens = ring.replicas(&key, 3);
-    assert_eq!(tokens, vec![&MyNode(1), &MyNode(3), &MyNode(4)]);
+    assert_eq!(tokens, vec![&MyNode(4), &MyNode(5), &MyNode(1)]);
+}
+
+#[test]
+fn builder_single_probe() {
+    // With a single probe, multi-probe hashing degenerates into the classic
+    // consistent hashing: key is owned by the first node clockwise from its
+    // position.
+    let ring = HashRingBuilder::new()
+        .probe_count(1)
+        .seeds(DEFAULT_SEED1, DEFAULT_SEED2)
+        .build();
+    assert_eq!(ring.probe_count(), 1);
+    for _ in 0..10 {
+        ring.add(Node::random());
+    }
+
+    for key in 0..1000u64 {
+        let node = ring.node(&key).unwrap();
+        let pos = ring.position(&key);
+        let expected = ring
+            .key_range(node.position())
+            .expect("non-empty ring")
+            .contains(&pos);
+        assert!(expected, "key {key} is not in the range of its node");
+    }
+}
+
+#[test]
+fn builder_custom_partitioner() {
+    // Partitioner placing everything at the position equal to hashed value of
+    // the standard library hasher.
+    #[derive(Clone, Default)]
+    struct StdPartitioner;
+
+    impl<K: Hash> Partitioner<K> for StdPartitioner {
+        fn position(&self, key: &K) -> RingPosition {
+            self.position_seeded(key, 0)
+        }
+
+        fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+            (0..k as u64).map(move |seed| self.position_seeded(key, seed))
+        }
+
+        fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            key.hash(&mut hasher);
+            hasher.finish()
+        }
+    }
+
+    let ring = HashRingBuilder::new()
+        .partitioner(StdPartitioner)
+        .probe_count(5)
+        .build();
+    assert_eq!(ring.probe_count(), 5);
+
+    let nodes = (0..10).map(|id| Node { id }).collect::<Vec<_>>();
+    nodes.iter().for_each(|node| ring.add(*node));
+    assert_eq!(ring.len(), nodes.len());
+    assert_eq!(ring.position(&nodes[0]), StdPartitioner.position(&nodes[0]));
+
+    for key in 0..1000u64 {
+        let node = ring.node(&key).unwrap();
+        assert!(nodes.contains(&node));
+    }
+
+    ring.remove(&nodes[0]);
+    assert_eq!(ring.len(), nodes.len() - 1);
+}
+
+/// Creates a ring with a random number of random nodes.
+fn random_ring(max_nodes: usize, probe_count: usize) -> HashRing<Node> {
+    let ring = HashRingBuilder::new().probe_count(probe_count).build();
+    let num_nodes = 1 + random::<u64>() as usize % max_nodes;
+    for _ in 0..num_nodes {
+        ring.add(Node::random());
+    }
+    ring
+}
+
+#[test]
+fn replicas_start_at_primary_node() {
+    for probe_count in [1, 2, 7, 23, 50] {
+        for _ in 0..20 {
+            let ring = random_ring(100, probe_count);
+            for _ in 0..100 {
+                let key = random::<u64>();
+                let n = 1 + random::<u64>() as usize % 5;
+                let primary = ring.node(&key).expect("non-empty ring");
+                let replicas = ring.replicas(&key, n);
+                assert_eq!(replicas[0], primary);
+                assert_eq!(replicas.len(), n.min(ring.len()));
+            }
+        }
+    }
+}
+
+#[test]
+fn replicas_follow_probe_position() {
+    for _ in 0..20 {
+        let ring = random_ring(100, 23);
+        for _ in 0..100 {
+            let key = random::<u64>();
+            let pos = ring.probe_position(&key).expect("non-empty ring");
+            let replicas = ring.replicas(&key, ring.len());
+
+            // The first replica owns the winning probe position.
+            let range = ring.key_range(replicas[0].position()).unwrap();
+            assert!(range.contains(&pos) || range.covers_whole_ring());
+
+            // Replicas are distinct and ordered clockwise from the probe.
+            let distances = replicas
+                .iter()
+                .map(|token| token.position().wrapping_sub(pos))
+                .collect::<Vec<_>>();
+            assert!(distances.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+}
+
+#[test]
+fn replicas_on_empty_ring() {
+    let ring = HashRing::<Node>::new();
+    assert!(ring.replicas(&"key", 3).is_empty());
+    assert_eq!(ring.probe_position(&"key"), None);
+
+    let ring = random_ring(10, 23);
+    assert!(ring.replicas(&"key", 0).is_empty());
+}
+
+#[test]
+fn membership() {
+    let ring = HashRing::new();
+    let nodes = (0..10).map(|id| Node { id }).collect::<Vec<_>>();
+    nodes.iter().for_each(|node| ring.add(*node));
+    let inserted = Node { id: 42 };
+    ring.insert(12345, inserted);
+
+    // Tokens are listed in the order of positions.
+    let tokens = ring.nodes().collect::<Vec<_>>();
+    assert_eq!(tokens.len(), 11);
+    assert!(tokens.windows(2).all(|w| w[0].position() < w[1].position()));
+    assert_eq!(
+        ring.nodes().rev().map(|t| t.position()).collect::<Vec<_>>(),
+        tokens
+            .iter()
+            .rev()
+            .map(|t| t.position())
+            .collect::<Vec<_>>()
+    );
+
+    for node in nodes.iter().chain([&inserted]) {
+        assert!(ring.contains(node));
+        let token = ring.token_of(node).unwrap();
+        assert_eq!(token.node(), node);
+        assert_eq!(ring.get(token.position()), Some(token));
+    }
+    assert_eq!(ring.token_of(&inserted).unwrap().position(), 12345);
+    assert!(!ring.contains(&Node { id: 100 }));
+    assert!(ring.token_of(&Node { id: 100 }).is_none());
+    assert!(ring.get(12346).is_none());
+
+    // Walk the ring in both directions.
+    let start = tokens[3].position();
+    let clockwise = ring
+        .tokens(start, RingDirection::Clockwise)
+        .map(|t| t.position())
+        .collect::<Vec<_>>();
+    let counter_clockwise = ring
+        .tokens(start, RingDirection::CounterClockwise)
+        .map(|t| t.position())
+        .collect::<Vec<_>>();
+    assert_eq!(clockwise.len(), 11);
+    assert_eq!(clockwise[0], start);
+    assert_eq!(counter_clockwise[0], start);
+    assert_eq!(
+        counter_clockwise[1..],
+        clockwise[1..].iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    // Remove by position.
+    let removed = ring.remove_at(12345).unwrap();
+    assert_eq!(removed.node(), &inserted);
+    assert!(!ring.contains(&inserted));
+    assert!(ring.remove_at(12345).is_none());
+    assert_eq!(ring.len(), 10);
+
+    let epoch = ring.epoch();
+    ring.clear();
+    assert!(ring.is_empty());
+    assert_eq!(ring.nodes().count(), 0);
+    assert_eq!(ring.epoch(), epoch + 10);
+    assert!(nodes.iter().all(|node| !ring.contains(node)));
+}
+
+#[test]
+fn owned_tokens() {
+    let ring = random_ring(20, 23);
+    for _ in 0..100 {
+        let key = random::<u64>();
+        let token = ring.node(&key).unwrap();
+        let owned = ring.node_owned(&key).unwrap();
+        assert_eq!(owned, token);
+        assert_eq!(owned.position(), token.position());
+        assert_eq!(owned.node(), token.node());
+        assert_eq!(token.clone().into_owned(), owned);
+
+        let replicas = ring.replicas(&key, 3);
+        let owned = ring.replicas_owned(&key, 3);
+        assert_eq!(owned.len(), ring.len().min(3));
+        for (owned, token) in owned.iter().zip(&replicas) {
+            assert_eq!(*owned, *token);
+            assert_eq!(owned.node(), token.node());
+        }
+    }
+
+    // Owned tokens outlive the ring, and can be sent to other threads.
+    let (token, expected) = {
+        let ring = random_ring(5, 23);
+        let expected = *ring.node(&"key").unwrap();
+        (ring.node_owned(&"key").unwrap(), expected)
+    };
+    let node = std::thread::spawn(move || token.into_node())
+        .join()
+        .unwrap();
+    assert_eq!(node, expected);
+
+    assert!(HashRing::<Node>::new().node_owned(&"key").is_none());
+    assert!(HashRing::<Node>::new().replicas_owned(&"key", 3).is_empty());
+}
+
+#[test]
+fn replicas_distinct() {
+    // Every node is placed at several positions.
+    let ring = HashRing::new();
+    for id in 0..10 {
+        for _ in 0..5 {
+            ring.insert(random(), Node { id });
+        }
+    }
+
+    for _ in 0..100 {
+        let key = random::<u64>();
+        let all = ring.replicas(&key, ring.len());
+        for n in [1, 3, 10, 20] {
+            let replicas = ring.replicas_distinct(&key, n);
+            assert_eq!(replicas.len(), n.min(10));
+            assert_eq!(replicas[0], ring.node(&key).unwrap());
+
+            // The first occurrence of each node, in the clockwise order.
+            let mut expected = Vec::new();
+            for token in &all {
+                if !expected.contains(token.node()) {
+                    expected.push(*token.node());
+                }
+            }
+            expected.truncate(n);
+            let nodes = replicas.iter().map(|t| *t.node()).collect::<Vec<_>>();
+            assert_eq!(nodes, expected);
+        }
+    }
+
+    let empty = HashRing::<Node>::new();
+    assert!(empty.replicas_distinct(&"key", 3).is_empty());
+}
+
+#[test]
+fn replicas_distinct_by_id() {
+    // Several disks per machine, disks of the same machine are distinct nodes.
+    let ring = HashRing::new();
+    for machine in 0..5u64 {
+        for disk in 0..4u64 {
+            ring.add((machine, disk));
+        }
+    }
+
+    for _ in 0..100 {
+        let key = random::<u64>();
+        let replicas = ring.replicas_distinct_by(&key, 3, |(machine, _)| *machine);
+        assert_eq!(replicas.len(), 3);
+        assert_eq!(replicas[0], ring.node(&key).unwrap());
+        let mut machines = replicas.iter().map(|t| t.node().0).collect::<Vec<_>>();
+        machines.sort_unstable();
+        machines.dedup();
+        assert_eq!(machines.len(), 3);
+
+        // More replicas than machines.
+        assert_eq!(ring.replicas_distinct_by(&key, 10, |(m, _)| *m).len(), 5);
+    }
+}
+
+#[test]
+fn ownership_matches_node() {
+    check_ownership(100_000);
+}
+
+#[test]
+#[ignore = "slow, run with `cargo test --release -- --ignored`"]
+fn ownership_matches_node_exhaustive() {
+    check_ownership(5_000_000);
+}
+
+/// Cross-checks the ownership map against key lookups.
+fn check_ownership(num_keys: usize) {
+    let num_rings = 10;
+    for _ in 0..num_rings {
+        let ring = random_ring(50, 23);
+        let map = ring.ownership();
+        assert_eq!(map.len(), ring.len());
+
+        // Intervals cover the whole ring without gaps.
+        for (i, (range, _)) in map.iter().enumerate() {
+            let (prev, _) = &map[(i + map.len() - 1) % map.len()];
+            assert_eq!(range.start, prev.end);
+        }
+
+        for _ in 0..num_keys / num_rings {
+            let key = random::<u64>();
+            let node = ring.node(&key).expect("non-empty ring");
+            let pos = ring.probe_position(&key).expect("non-empty ring");
+
+            // Winning probe falls into the interval captured by the owner.
+            let idx = map.partition_point(|(range, _)| range.end <= pos) % map.len();
+            let (range, owner) = &map[idx];
+            assert!(range.contains(&pos) || range.covers_whole_ring());
+            assert_eq!(owner, &node);
+
+            assert!(ring.owns(&node, &key));
+            let (_, other) = &map[key as usize % map.len()];
+            assert_eq!(ring.owns(other, &key), other == &node);
+        }
+    }
+}
+
+#[test]
+fn ownership_of_inserted_nodes() {
+    let ring = HashRing::new();
+    (0..10).for_each(|node| ring.add(node));
+    ring.insert(42, 20);
+    ring.insert(u64::MAX / 2, 20);
+
+    let intervals = ring.intervals(&20).expect("node exists");
+    assert_eq!(intervals.len(), 2);
+    assert_eq!(intervals[0].end, 42);
+    assert_eq!(intervals[1].end, u64::MAX / 2);
+
+    for key in 0..10_000u64 {
+        let node = ring.node(&key).expect("non-empty ring");
+        let pos = ring.probe_position(&key).expect("non-empty ring");
+        assert!(ring.owns(node.node(), &key));
+        assert_eq!(
+            ring.owns(&20, &key),
+            intervals.iter().any(|range| range.contains(&pos))
+        );
+    }
+}
+
+#[test]
+fn ownership_on_empty_ring() {
+    let ring = HashRing::<Node>::new();
+    let node = Node::random();
+    assert!(ring.ownership().is_empty());
+    assert!(ring.intervals(&node).is_none());
+    assert!(!ring.owns(&node, &"key"));
+
+    // Nodes not on the ring do not own any intervals.
+    ring.add(Node::random());
+    assert!(ring.intervals(&node).is_none());
+    assert!(!ring.owns(&node, &"key"));
+}
+
+#[test]
+fn weighted_distribution() {
+    let ring = HashRing::new();
+    let nodes = (0..12)
+        .map(|id| (Node { id }, 1 + id as u32 % 4))
+        .collect::<Vec<_>>();
+    nodes
+        .iter()
+        .for_each(|(node, weight)| ring.add_weighted(*node, *weight));
+    let total_weight = nodes.iter().map(|(_, weight)| *weight).sum::<u32>();
+
+    let num_keys = 100_000;
+    let mut loads = HashMap::new();
+    for key in 0..num_keys {
+        let node = ring.node(&key).expect("non-empty ring");
+        *loads.entry(*node).or_insert(0) += 1;
+    }
+
+    // Every node is loaded according to its weight share (within tolerance).
+    // The tolerance is wide, as even without weights, nodes with short arcs
+    // get noticeably fewer keys on a ring this small.
+    let share = |weight: u32| f64::from(num_keys) * f64::from(weight) / f64::from(total_weight);
+    let mut class_loads = BTreeMap::new();
+    for (node, weight) in &nodes {
+        let expected = share(*weight);
+        let actual = f64::from(*loads.get(node).unwrap_or(&0));
+        assert!(
+            (0.5..1.5).contains(&(actual / expected)),
+            "node of weight {weight} got {actual} keys, expected {expected}"
+        );
+        *class_loads.entry(*weight).or_insert(0.0) += actual;
+    }
+
+    // Nodes of the same weight get their share of keys (within a tighter
+    // tolerance), so heavier nodes get more keys.
+    for (weight, actual) in &class_loads {
+        let count = nodes.iter().filter(|(_, w)| w == weight).count();
+        let expected = share(*weight) * count as f64;
+        assert!(
+            (0.75..1.25).contains(&(actual / expected)),
+            "nodes of weight {weight} got {actual} keys, expected {expected}"
+        );
+    }
+    let class_loads = class_loads.into_values().collect::<Vec<_>>();
+    assert!(class_loads.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn weighted_add_remove() {
+    let ring = HashRing::new();
+    let node = Node::random();
+    assert_eq!(ring.weight(&node), None);
+
+    ring.add_weighted(node, 3);
+    assert_eq!(ring.weight(&node), Some(3));
+
+    // Re-adding a node resets its weight.
+    ring.add(node);
+    assert_eq!(ring.weight(&node), Some(1));
+
+    ring.add_weighted(node, 5);
+    ring.remove(&node);
+    assert_eq!(ring.weight(&node), None);
+    assert!(ring.is_empty());
+}
+
+/// Partitioner placing all the nodes at the same position.
+///
+/// Re-seeded positions are either distinct (if `reseed` is set) or the same.
+#[derive(Clone)]
+struct CollidingPartitioner {
+    reseed: bool,
+}
+
+impl<K: Hash> Partitioner<K> for CollidingPartitioner {
+    fn position(&self, _key: &K) -> RingPosition {
+        42
+    }
+
+    fn positions(&self, key: &K, k: usize) -> impl Iterator<Item = RingPosition> {
+        (0..k as u64).map(move |seed| self.position_seeded(key, seed))
+    }
+
+    fn position_seeded(&self, key: &K, seed: RingPosition) -> RingPosition {
+        if !self.reseed {
+            return 42;
+        }
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[test]
+fn collisions_resolved() {
+    let ring = HashRingBuilder::new()
+        .partitioner(CollidingPartitioner { reseed: true })
+        .build();
+    let node1 = Node { id: 1 };
+    let node2 = Node { id: 2 };
+    let node3 = Node { id: 3 };
+
+    let pos1 = ring.try_add(node1).unwrap();
+    let pos2 = ring.try_add(node2).unwrap();
+    let pos3 = ring.try_add(node3).unwrap();
+    assert_eq!(pos1, 42);
+    assert_ne!(pos2, pos1);
+    assert_ne!(pos3, pos1);
+    assert_ne!(pos3, pos2);
+    assert_eq!(ring.len(), 3);
+
+    // Adding the same node again is a no-op.
+    assert_eq!(ring.try_add(node2), Ok(pos2));
+    assert_eq!(ring.len(), 3);
+
+    // Placement is deterministic.
+    let other = HashRingBuilder::new()
+        .partitioner(CollidingPartitioner { reseed: true })
+        .build();
+    other.add(node1);
+    other.add(node2);
+    assert_eq!(other.try_add(node3), Ok(pos3));
+
+    // Removing the first node doesn't affect colliding ones.
+    ring.remove(&node1);
+    assert_eq!(ring.len(), 2);
+    assert!(ring.intervals(&node1).is_none());
+    assert!(ring.intervals(&node2).is_some());
+
+    // Colliding nodes can still be found and removed.
+    assert_eq!(ring.try_add(node3), Ok(pos3));
+    ring.remove(&node3);
+    ring.remove(&node2);
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn collisions_resolved_concurrently() {
+    fn check<S: RingStorage>(storage: S) {
+        let ring = HashRingBuilder::new()
+            .partitioner(CollidingPartitioner { reseed: true })
+            .storage(storage)
+            .build();
+
+        // All the nodes collide, and every node is added by several threads.
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for id in 0..50 {
+                        ring.try_add(Node { id }).unwrap();
+                    }
+                });
+            }
+        });
+
+        // No node is evicted by a concurrently added one, nor added twice.
+        assert_eq!(ring.len(), 50);
+        for id in 0..50 {
+            assert!(
+                ring.intervals(&Node { id }).is_some(),
+                "node {id} is evicted"
+            );
+        }
+    }
+
+    check(SkipMapStorage);
+    check(SortedArrayStorage);
+}
+
+#[test]
+fn collisions_unresolved() {
+    let ring = HashRingBuilder::new()
+        .partitioner(CollidingPartitioner { reseed: false })
+        .build();
+    let node1 = Node { id: 1 };
+    let node2 = Node { id: 2 };
+
+    assert_eq!(ring.try_add(node1), Ok(42));
+    let err = ring.try_add(node2).unwrap_err();
+    assert_eq!(err.position, 42);
+
+    // Node is not silently evicted.
+    ring.add(node2);
+    assert_eq!(ring.len(), 1);
+    assert_eq!(ring.node(&0).as_deref(), Some(&node1));
+    ring.remove(&node2);
+    assert_eq!(ring.len(), 1);
 }
@@ -0,0 +1,171 @@
+use {
+    mpchash::{HashRing, NodeHealth},
+    std::collections::HashSet,
+};
+
+fn ring(nodes: u64) -> HashRing<u64> {
+    let ring = HashRing::new();
+    (0..nodes).for_each(|node| ring.add(node));
+    ring
+}
+
+#[test]
+fn set_health() {
+    let ring = ring(10);
+    let epoch = ring.epoch();
+    assert_eq!(ring.health(&3), Some(NodeHealth::Up));
+    assert_eq!(ring.health(&42), None);
+
+    for health in [
+        NodeHealth::Suspect,
+        NodeHealth::Down,
+        NodeHealth::Draining,
+        NodeHealth::Up,
+    ] {
+        assert!(ring.set_health(&3, health));
+        assert_eq!(ring.health(&3), Some(health));
+    }
+    assert!(!ring.set_health(&42, NodeHealth::Down));
+
+    // Every change of health increments the epoch, but is not a membership
+    // change.
+    assert_eq!(ring.epoch(), epoch + 4);
+    assert!(ring.set_health(&3, NodeHealth::Up));
+    assert_eq!(ring.epoch(), epoch + 4);
+    assert_eq!(ring.len(), 10);
+
+    // Snapshots capture health.
+    ring.set_health(&3, NodeHealth::Down);
+    let snapshot = ring.snapshot();
+    ring.set_health(&3, NodeHealth::Up);
+    assert_eq!(snapshot.health(&3), Some(NodeHealth::Down));
+
+    // Removed nodes lose their health, re-added nodes are up.
+    ring.set_health(&4, NodeHealth::Down);
+    ring.remove(&4);
+    assert_eq!(ring.health(&4), None);
+    ring.add(4);
+    assert_eq!(ring.health(&4), Some(NodeHealth::Up));
+
+    // All positions of a node are updated.
+    let ring = HashRing::new();
+    ring.insert(10, 1u64);
+    ring.insert(20, 2u64);
+    ring.insert(30, 1u64);
+    ring.set_health(&1, NodeHealth::Down);
+    assert_eq!(ring.node_failover(&"key").unwrap().token.node(), &2);
+
+    // Replacing a node at the same position resets health.
+    ring.insert(10, 3);
+    assert_eq!(ring.health(&3), Some(NodeHealth::Up));
+}
+
+#[test]
+fn node_failover() {
+    let ring = ring(20);
+    let before = (0..500u64)
+        .map(|key| *ring.node(&key).unwrap())
+        .collect::<Vec<_>>();
+    for key in 0..500u64 {
+        let primary = ring.node(&key).unwrap();
+        let token = ring.node_failover(&key).unwrap();
+        assert_eq!(token.token, primary);
+        assert!(!token.is_substitute());
+    }
+
+    let down = HashSet::from([1, 5, 6, 7, 12]);
+    for node in &down {
+        ring.set_health(node, NodeHealth::Down);
+    }
+    ring.set_health(&2, NodeHealth::Suspect);
+    ring.set_health(&3, NodeHealth::Draining);
+    let unavailable = |node: &u64| down.contains(node) || *node == 3;
+
+    for key in 0..500u64 {
+        let primary = ring.node(&key).unwrap();
+        let token = ring.node_failover(&key).unwrap();
+        assert!(!unavailable(token.token.node()));
+        if unavailable(primary.node()) {
+            assert_eq!(token.replaces.as_ref(), Some(&primary));
+
+            // The first available node clockwise from the primary.
+            let replicas = ring.replicas(&key, ring.len());
+            let next = replicas.iter().find(|t| !unavailable(t.node())).unwrap();
+            assert_eq!(&token.token, next);
+        } else {
+            assert_eq!(token.token, primary);
+            assert!(!token.is_substitute());
+        }
+    }
+
+    // Ownership is unchanged.
+    let owners = (0..500u64)
+        .map(|key| *ring.node(&key).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(owners, before);
+
+    // No available nodes.
+    (0..20).for_each(|node| {
+        ring.set_health(&node, NodeHealth::Down);
+    });
+    assert!(ring.node_failover(&"key").is_none());
+    assert!(ring.replicas_failover(&"key", 3).is_empty());
+    assert!(HashRing::<u64>::new().node_failover(&"key").is_none());
+}
+
+#[test]
+fn replicas_failover() {
+    let ring = ring(20);
+    let down = [1, 5, 6, 7, 12];
+    for node in &down {
+        ring.set_health(node, NodeHealth::Down);
+    }
+
+    for key in 0..500u64 {
+        for k in [1, 3, 5] {
+            let natural = ring.replicas(&key, k);
+            let replicas = ring.replicas_failover(&key, k);
+            assert_eq!(replicas.len(), k);
+            assert!(replicas.iter().all(|t| !down.contains(t.token.node())));
+
+            // Available natural replicas are kept as is.
+            let kept = replicas
+                .iter()
+                .filter(|t| !t.is_substitute())
+                .map(|t| t.token.clone())
+                .collect::<Vec<_>>();
+            let available = natural
+                .iter()
+                .filter(|t| !down.contains(t.node()))
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(kept, available);
+
+            // Each unavailable natural replica is substituted exactly once.
+            let replaced = replicas
+                .iter()
+                .filter_map(|t| t.replaces.clone())
+                .collect::<Vec<_>>();
+            let unavailable = natural
+                .iter()
+                .filter(|t| down.contains(t.node()))
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(replaced, unavailable);
+
+            // Substitutes are the next available nodes clockwise.
+            let all = ring.replicas(&key, ring.len());
+            let expected = all
+                .iter()
+                .filter(|t| !down.contains(t.node()))
+                .take(k)
+                .cloned()
+                .collect::<Vec<_>>();
+            let tokens = replicas.iter().map(|t| t.token.clone()).collect::<Vec<_>>();
+            assert_eq!(tokens, expected);
+        }
+    }
+
+    // Not enough available nodes.
+    assert_eq!(ring.replicas_failover(&"key", 20).len(), 15);
+}
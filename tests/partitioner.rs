@@ -1,4 +1,11 @@
-use mpchash::{DefaultPartitioner, Partitioner, Xxh3Partitioner};
+use mpchash::{
+    DefaultPartitioner,
+    Partitioner,
+    Xxh3Partitioner,
+    DEFAULT_PROBE_SEEDS,
+    DEFAULT_SEED1,
+    DEFAULT_SEED2,
+};
 
 #[test]
 fn default_partitioner() {
@@ -32,3 +39,224 @@ fn position_seeded() {
         0x75a073dcf2e9322a
     );
 }
+
+#[test]
+fn with_seeds() {
+    // The first seed defines the main position of a key.
+    let partitioner = Xxh3Partitioner::with_seeds(DEFAULT_SEED1, 1);
+    let default = Xxh3Partitioner::new();
+    for key in [0u64, 1, 123456] {
+        assert_eq!(partitioner.position(&key), default.position(&key));
+        assert_eq!(
+            partitioner.positions(&key, 1).next(),
+            Some(default.position(&key))
+        );
+    }
+
+    // Different seeds produce different probe sequences.
+    let other = Xxh3Partitioner::with_seeds(DEFAULT_SEED1, 2);
+    assert_ne!(
+        partitioner.positions(&0u64, 5).collect::<Vec<_>>(),
+        other.positions(&0u64, 5).collect::<Vec<_>>()
+    );
+
+    // Default probes are unseeded, so explicit default seeds don't reproduce
+    // the default partitioner.
+    let explicit = Xxh3Partitioner::with_seeds(DEFAULT_SEED1, DEFAULT_SEED2);
+    assert_ne!(
+        explicit.positions(&0u64, 5).collect::<Vec<_>>(),
+        default.positions(&0u64, 5).collect::<Vec<_>>()
+    );
+
+    // Default probe seeds do.
+    let explicit = Xxh3Partitioner::with_probe_seeds(DEFAULT_SEED1, DEFAULT_PROBE_SEEDS);
+    for key in [0u64, 1, 123456] {
+        assert_eq!(explicit.position(&key), default.position(&key));
+        assert_eq!(
+            explicit.positions(&key, 5).collect::<Vec<_>>(),
+            default.positions(&key, 5).collect::<Vec<_>>()
+        );
+    }
+}
+
+/// Checks properties shared by all the partitioners.
+#[cfg(any(
+    feature = "fnv",
+    feature = "siphash",
+    feature = "murmur3",
+    feature = "blake3"
+))]
+fn check_partitioner<P>(partitioner: &P, seeded: &P)
+where
+    P: Partitioner<u64> + Partitioner<&'static str> + mpchash::WirePartitioner + Clone,
+{
+    // Positions are deterministic, and depend on seeds.
+    assert_eq!(
+        partitioner.position(&1u64),
+        partitioner.clone().position(&1u64)
+    );
+    assert_ne!(partitioner.position(&1u64), partitioner.position(&2u64));
+    assert_ne!(partitioner.position(&1u64), seeded.position(&1u64));
+    assert_ne!(
+        partitioner.position_seeded(&"key", 1),
+        partitioner.position_seeded(&"key", 2)
+    );
+
+    // Probes are distinct.
+    let probes = partitioner.positions(&"key", 23).collect::<Vec<_>>();
+    let mut unique = probes.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(probes.len(), 23);
+    assert_eq!(unique.len(), 23);
+
+    // Keys are spread over the ring evenly.
+    let ring = mpchash::HashRingBuilder::new()
+        .partitioner(seeded.clone())
+        .build::<u64>();
+    (0..20).for_each(|i| ring.add(i));
+    let stats = ring.stats(0..20_000u64);
+    assert!(stats.peak_to_average() < 1.5);
+
+    // Ring survives encoding.
+    let bytes = ring.encode(&mpchash::RawCodec);
+    let restored =
+        mpchash::HashRing::<u64, P>::decode(&bytes, &mpchash::RawCodec).expect("valid ring");
+    for key in 0..100u64 {
+        assert_eq!(
+            restored.node(&key).map(|t| *t.node()),
+            ring.node(&key).map(|t| *t.node())
+        );
+    }
+}
+
+#[cfg(feature = "fnv")]
+#[test]
+fn fnv1a() {
+    use mpchash::{Fnv1aPartitioner, RawKey};
+
+    // Reference implementation.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    let partitioner = Fnv1aPartitioner::new();
+    assert_eq!(partitioner.position(&RawKey("")), 0xcbf29ce484222325);
+    assert_eq!(partitioner.position(&RawKey("a")), 0xaf63dc4c8601ec8c);
+    assert_eq!(partitioner.position(&RawKey("foobar")), 0x85944171f73967e8);
+    for key in [0u64, 1, 123456] {
+        assert_eq!(partitioner.position(&key), fnv1a(&key.to_le_bytes()));
+    }
+    check_partitioner(&partitioner, &Fnv1aPartitioner::with_seeds(1, 2));
+}
+
+#[cfg(feature = "siphash")]
+#[test]
+fn siphash() {
+    use mpchash::{RawKey, SipHashPartitioner};
+
+    // Test vectors from the SipHash paper: key is `00..0f`, message is `00..0e`.
+    let partitioner = SipHashPartitioner::with_keys(0x0706050403020100, 0x0f0e0d0c0b0a0908);
+    let message = (0u8..15).collect::<Vec<_>>();
+    assert_eq!(partitioner.position(&RawKey(&[])), 0x726fdb47dd0e0e31);
+    assert_eq!(partitioner.position(&RawKey(&message)), 0xa129ca6149be45e5);
+    assert_eq!(partitioner.keys(), (0x0706050403020100, 0x0f0e0d0c0b0a0908));
+
+    // Different keys produce different positions.
+    let other = SipHashPartitioner::with_keys(1, 2);
+    assert_ne!(partitioner.position(&"key"), other.position(&"key"));
+    check_partitioner(&partitioner, &other);
+    check_partitioner(
+        &SipHashPartitioner::new(),
+        &SipHashPartitioner::with_seeds(1, 2),
+    );
+
+    // Default key is random, never the publicly known zero key.
+    let (random1, random2) = (SipHashPartitioner::new(), SipHashPartitioner::new());
+    assert_ne!(random1.keys(), (0, 0));
+    assert_ne!(random1.keys(), random2.keys());
+    assert_ne!(random1.position(&"key"), random2.position(&"key"));
+}
+
+#[cfg(feature = "murmur3")]
+#[test]
+fn murmur3() {
+    use mpchash::{Murmur3Partitioner, RawKey};
+
+    let partitioner = Murmur3Partitioner::new();
+    assert_eq!(partitioner.position(&RawKey("")), 0);
+    assert_eq!(
+        partitioner.position(&RawKey("The quick brown fox jumps over the lazy dog")),
+        0xe34bbc7bbc071b6c
+    );
+    for key in [0u64, 1, 123456] {
+        let hash = murmur3::murmur3_x64_128(&mut &key.to_le_bytes()[..], 0).expect("hash");
+        assert_eq!(partitioner.position(&key), hash as u64);
+    }
+    check_partitioner(&partitioner, &Murmur3Partitioner::with_seeds(1, 2));
+}
+
+#[cfg(feature = "blake3")]
+#[test]
+fn blake3() {
+    use mpchash::{Blake3Partitioner, RawKey};
+
+    let partitioner = Blake3Partitioner::new();
+    assert_eq!(partitioner.position(&RawKey("")), 0xa6a1f9f5b94913af);
+    assert_eq!(partitioner.position(&RawKey("abc")), 0x33514638acb33764);
+
+    // Non-zero seeds switch to the keyed mode.
+    let mut key = [0; 32];
+    key[0] = 1;
+    let hash = blake3::keyed_hash(&key, b"abc");
+    assert_eq!(
+        partitioner.position_seeded(&RawKey("abc"), 1),
+        u64::from_le_bytes(hash.as_bytes()[..8].try_into().expect("hash size"))
+    );
+    check_partitioner(&partitioner, &Blake3Partitioner::with_seeds(1, 2));
+}
+
+#[test]
+fn keyed() {
+    use mpchash::{KeyedPartitioner, SecretKey};
+
+    let secret = SecretKey::from(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+    let partitioner = KeyedPartitioner::with_secret(secret);
+    assert_eq!(partitioner.secret(), &secret);
+
+    // Processes sharing the secret compute the same positions.
+    let shared = KeyedPartitioner::with_secret(SecretKey::from_bytes(*secret.as_bytes()));
+    for key in [0u64, 1, 123456] {
+        assert_eq!(partitioner.position(&key), shared.position(&key));
+        assert_eq!(
+            partitioner.positions(&key, 5).collect::<Vec<_>>(),
+            shared.positions(&key, 5).collect::<Vec<_>>()
+        );
+    }
+
+    // Positions depend on the secret, and differ from the public partitioner.
+    let other = KeyedPartitioner::with_secret(SecretKey::from(1u128));
+    let public = Xxh3Partitioner::new();
+    assert_ne!(partitioner.position(&"key"), other.position(&"key"));
+    assert_ne!(partitioner.position(&"key"), public.position(&"key"));
+
+    // Random secrets.
+    let random1 = KeyedPartitioner::new();
+    let random2 = KeyedPartitioner::new();
+    assert_ne!(random1.secret(), random2.secret());
+    assert_ne!(random1.position(&"key"), random2.position(&"key"));
+
+    // Secret is never printed.
+    let debug = format!("{partitioner:?}");
+    assert!(debug.contains("SecretKey(..)"));
+    assert!(!debug.contains("0123"));
+
+    // Keys are spread over the ring evenly.
+    let ring = mpchash::HashRingBuilder::new()
+        .partitioner(partitioner)
+        .build::<u64>();
+    (0..20).for_each(|i| ring.add(i));
+    assert!(ring.stats(0..20_000u64).peak_to_average() < 1.5);
+}
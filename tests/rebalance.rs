@@ -0,0 +1,159 @@
+use {
+    mpchash::{HashRing, RebalancePlan, Xxh3Partitioner},
+    rand::random,
+};
+
+#[derive(Hash, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Node {
+    id: u64,
+}
+
+impl Node {
+    fn random() -> Self {
+        Self { id: random() }
+    }
+}
+
+fn random_ring(num_nodes: usize, weighted: bool) -> HashRing<Node> {
+    let ring = HashRing::new();
+    for _ in 0..num_nodes {
+        ring.add_weighted(Node::random(), random_weight(weighted));
+    }
+    ring
+}
+
+fn random_weight(weighted: bool) -> u32 {
+    if weighted {
+        1 + random::<u32>() % 4
+    } else {
+        1
+    }
+}
+
+/// Checks that for every key, the plan lists exactly the transfers required to
+/// get the key to its new replicas.
+#[track_caller]
+fn assert_plan(ring: &HashRing<Node>, plan: &RebalancePlan<Node, Xxh3Partitioner>, r: usize) {
+    let target = plan.target();
+    for _ in 0..1000 {
+        let key = random::<u64>();
+        let (Some(old_primary), Some(probe)) = (ring.node(&key), target.probe_position(&key))
+        else {
+            continue;
+        };
+        let old = ring.replicas(&key, r);
+        let mut expected = target
+            .replicas(&key, r)
+            .iter()
+            .map(|token| *token.node())
+            .filter(|node| !old.iter().any(|token| token.node() == node))
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        let mut actual = plan
+            .transfers()
+            .iter()
+            .filter(|t| t.from == *old_primary)
+            .filter(|t| t.range.contains(&probe) || t.range.covers_whole_ring())
+            .map(|t| t.to)
+            .collect::<Vec<_>>();
+        actual.sort();
+
+        assert_eq!(actual, expected, "unexpected transfers for key {key}");
+    }
+}
+
+#[test]
+fn plan_add() {
+    for weighted in [false, true] {
+        for r in [1, 3] {
+            for _ in 0..5 {
+                let ring = random_ring(20, weighted);
+                let node = Node::random();
+                let plan = ring.plan_add_weighted(&node, random_weight(weighted), r);
+                assert!(!plan.is_empty());
+                assert!(plan.transfers().iter().any(|t| t.to == node));
+                assert!(plan.transfers().iter().all(|t| t.from != node));
+                assert_plan(&ring, &plan, r);
+
+                // Live ring is not modified.
+                assert_eq!(ring.len(), 20);
+                assert!(ring.intervals(&node).is_none());
+                assert_eq!(plan.target().len(), 21);
+            }
+        }
+    }
+}
+
+#[test]
+fn plan_remove() {
+    for weighted in [false, true] {
+        for r in [1, 3] {
+            for _ in 0..5 {
+                let ring = random_ring(20, weighted);
+                let node = *ring.node(&random::<u64>()).expect("non-empty ring");
+                let plan = ring.plan_remove(&node, r);
+                assert!(!plan.is_empty());
+                assert!(plan.transfers().iter().all(|t| t.to != node));
+                assert_plan(&ring, &plan, r);
+
+                // Live ring is not modified.
+                assert_eq!(ring.len(), 20);
+                assert!(ring.intervals(&node).is_some());
+                assert_eq!(plan.target().len(), 19);
+            }
+        }
+    }
+}
+
+#[test]
+fn plan_inserted_nodes() {
+    for r in [1, 3] {
+        for _ in 0..5 {
+            let ring = random_ring(10, false);
+            let inserted = Node::random();
+            ring.insert(random(), inserted);
+
+            // Keys of the inserted node are moved when it is removed.
+            let plan = ring.plan_remove(&inserted, r);
+            assert!(plan.transfers().iter().any(|t| t.from == inserted));
+            assert!(plan.transfers().iter().all(|t| t.to != inserted));
+            assert_plan(&ring, &plan, r);
+            assert!(!plan.target().contains(&inserted));
+            assert_eq!(plan.target().len(), 10);
+
+            // The added node captures keys from the inserted one too.
+            let node = Node::random();
+            let plan = ring.plan_add(&node, r);
+            assert!(plan
+                .transfers()
+                .iter()
+                .any(|t| t.from == inserted && t.to == node));
+            assert_plan(&ring, &plan, r);
+        }
+    }
+}
+
+#[test]
+fn plan_weight_change() {
+    let ring = random_ring(20, true);
+    let node = *ring.node(&random::<u64>()).expect("non-empty ring");
+    let weight = ring.weight(&node).expect("node is on the ring");
+    for new_weight in [weight + 1, weight.max(2) - 1] {
+        let plan = ring.plan_add_weighted(&node, new_weight, 2);
+        assert_eq!(plan.target().weight(&node), Some(new_weight));
+        assert_plan(&ring, &plan, 2);
+    }
+}
+
+#[test]
+fn plan_noop() {
+    let ring = random_ring(10, false);
+    let node = *ring.node(&"key").expect("non-empty ring");
+    assert!(ring.plan_add(&node, 3).is_empty());
+    assert!(ring.plan_remove(&Node::random(), 3).is_empty());
+
+    // Adding the first node moves no data.
+    let ring = HashRing::new();
+    assert!(ring.plan_add(&Node::random(), 3).is_empty());
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "serde")]
+
+use mpchash::{
+    HashRing,
+    HashRingBuilder,
+    KeyRange,
+    OwnedRingToken,
+    RingDirection,
+    StateError,
+    Xxh3Partitioner,
+};
+
+#[test]
+fn ring_roundtrip() {
+    let ring = HashRingBuilder::new().seeds(7, 11).build::<u64>();
+    (0..10).for_each(|i| ring.add(i));
+    ring.add_weighted(10, 3);
+    ring.insert(42, 100);
+
+    let json = serde_json::to_string(&ring).expect("serialize");
+    let restored: HashRing<u64> = serde_json::from_str(&json).expect("deserialize");
+
+    assert_eq!(restored.len(), ring.len());
+    assert_eq!(restored.epoch(), ring.epoch());
+    assert_eq!(restored.probe_count(), ring.probe_count());
+    assert_eq!(restored.weight(&10), Some(3));
+    assert_eq!(restored.to_state().tokens, ring.to_state().tokens);
+    for key in 0..1000u64 {
+        assert_eq!(
+            restored.node(&key).map(|token| *token.node()),
+            ring.node(&key).map(|token| *token.node())
+        );
+    }
+}
+
+#[test]
+fn inserted_tokens() {
+    let ring = HashRing::<u64>::new();
+    ring.add(1);
+    ring.insert(42, 2);
+
+    let state = ring.to_state();
+    let inserted = state
+        .tokens
+        .iter()
+        .map(|token| (token.node, token.inserted))
+        .collect::<Vec<_>>();
+    assert!(inserted.contains(&(1, false)));
+    assert!(inserted.contains(&(2, true)));
+
+    // Omitted fields fall back to defaults.
+    let json = r#"{
+        "probe_count": 23,
+        "partitioner": { "kind": "xxh3", "seed": 12345, "probe_seeds": [0, 0] },
+        "tokens": [{ "position": 42, "node": 2, "inserted": true }]
+    }"#;
+    let ring: HashRing<u64> = serde_json::from_str(json).expect("deserialize");
+    assert_eq!(ring.epoch(), 0);
+    assert_eq!(ring.len(), 1);
+    assert_eq!(ring.key_range(42), Some(KeyRange::new(42, 42)));
+}
+
+#[test]
+fn invalid_state() {
+    let ring = HashRing::<u64>::new();
+    ring.add(1);
+    ring.add(2);
+
+    // Tampered position.
+    let mut state = ring.to_state();
+    state.tokens[0].position = state.tokens[0].position.wrapping_add(1);
+    let pos = state.tokens[0].position;
+    assert_eq!(
+        HashRing::from_state(state).err(),
+        Some(StateError::PositionMismatch(pos))
+    );
+
+    // Tampered position is fine for inserted tokens.
+    let mut state = ring.to_state();
+    state.tokens[0].position = state.tokens[0].position.wrapping_add(1);
+    state.tokens[0].inserted = true;
+    assert!(HashRing::from_state(state).is_ok());
+
+    // Duplicate position.
+    let mut state = ring.to_state();
+    state.tokens[1].position = state.tokens[0].position;
+    state.tokens[1].inserted = true;
+    let pos = state.tokens[0].position;
+    assert_eq!(
+        HashRing::from_state(state).err(),
+        Some(StateError::DuplicatePosition(pos))
+    );
+
+    // Zero weight.
+    let mut state = ring.to_state();
+    state.tokens[0].weight = 0;
+    let pos = state.tokens[0].position;
+    assert_eq!(
+        HashRing::from_state(state).err(),
+        Some(StateError::ZeroWeight(pos))
+    );
+
+    // Zero probe count.
+    let mut state = ring.to_state();
+    state.probe_count = 0;
+    assert_eq!(
+        HashRing::from_state(state).err(),
+        Some(StateError::ZeroProbeCount)
+    );
+
+    // Different seeds change positions of the nodes.
+    let mut json = serde_json::to_value(&ring).expect("serialize");
+    json["partitioner"]["seed"] = 1.into();
+    let err = serde_json::from_value::<HashRing<u64>>(json)
+        .err()
+        .expect("error");
+    assert!(err.to_string().contains("doesn't match partitioner"));
+
+    // Unknown partitioner.
+    let mut json = serde_json::to_value(&ring).expect("serialize");
+    json["partitioner"]["kind"] = "fnv".into();
+    assert!(serde_json::from_value::<HashRing<u64>>(json).is_err());
+}
+
+#[test]
+fn partitioner_roundtrip() {
+    let partitioner = Xxh3Partitioner::with_seeds(1, 2);
+    let json = serde_json::to_string(&partitioner).expect("serialize");
+    assert_eq!(json, r#"{"kind":"xxh3","seed":1,"probe_seeds":[1,2]}"#);
+
+    let restored: Xxh3Partitioner = serde_json::from_str(&json).expect("deserialize");
+    assert_eq!(
+        mpchash::Partitioner::position(&restored, &"key"),
+        mpchash::Partitioner::position(&partitioner, &"key")
+    );
+}
+
+#[test]
+fn range_and_direction_roundtrip() {
+    let range = KeyRange::new(10u64, 20);
+    let json = serde_json::to_string(&range).expect("serialize");
+    assert_eq!(
+        serde_json::from_str::<KeyRange<u64>>(&json).expect("deserialize"),
+        range
+    );
+
+    for dir in [RingDirection::Clockwise, RingDirection::CounterClockwise] {
+        let json = serde_json::to_string(&dir).expect("serialize");
+        assert_eq!(
+            serde_json::from_str::<RingDirection>(&json).expect("deserialize"),
+            dir
+        );
+    }
+}
+
+#[test]
+fn owned_token_roundtrip() {
+    let ring = HashRing::new();
+    (0..10u64).for_each(|node| ring.add(node));
+    let token = ring.node_owned(&"key").expect("non-empty ring");
+    let json = serde_json::to_string(&token).expect("serialize");
+    let restored = serde_json::from_str::<OwnedRingToken<u64>>(&json).expect("deserialize");
+    assert_eq!(restored.position(), token.position());
+    assert_eq!(restored.node(), token.node());
+}
+
+#[cfg(all(feature = "fnv", feature = "siphash"))]
+#[test]
+fn alternative_partitioners() {
+    use mpchash::{Fnv1aPartitioner, SipHashPartitioner};
+
+    let partitioner = SipHashPartitioner::with_keys(1, 2);
+    let json = serde_json::to_string(&partitioner).expect("serialize");
+    assert_eq!(
+        json,
+        r#"{"kind":"siphash","keys":[1,2],"seed":0,"probe_seeds":[12345,67890]}"#
+    );
+    let restored: SipHashPartitioner = serde_json::from_str(&json).expect("deserialize");
+    assert_eq!(restored.keys(), (1, 2));
+
+    // Ring cannot be restored with a different partitioner.
+    let ring = HashRingBuilder::new()
+        .partitioner(Fnv1aPartitioner::new())
+        .build::<u64>();
+    ring.add(1);
+    let json = serde_json::to_string(&ring).expect("serialize");
+    assert!(serde_json::from_str::<HashRing<u64, Fnv1aPartitioner>>(&json).is_ok());
+    assert!(serde_json::from_str::<HashRing<u64>>(&json).is_err());
+    assert!(serde_json::from_str::<HashRing<u64, SipHashPartitioner>>(&json).is_err());
+}
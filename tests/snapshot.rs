@@ -0,0 +1,126 @@
+use {
+    mpchash::HashRing,
+    rand::random,
+    std::{sync::mpsc, thread},
+};
+
+#[derive(Hash, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Node {
+    id: u64,
+}
+
+impl Node {
+    fn random() -> Self {
+        Self { id: random() }
+    }
+}
+
+#[test]
+fn epochs() {
+    let ring = HashRing::new();
+    assert_eq!(ring.epoch(), 0);
+
+    let node = Node::random();
+    ring.add(node);
+    assert_eq!(ring.epoch(), 1);
+
+    // Clones share the epoch.
+    let clone = ring.clone();
+    clone.add(Node::random());
+    assert_eq!(ring.epoch(), 2);
+
+    ring.remove(&node);
+    assert_eq!(ring.epoch(), 3);
+
+    // Removing a missing node doesn't modify the ring.
+    ring.remove(&node);
+    assert_eq!(ring.epoch(), 3);
+}
+
+#[test]
+fn snapshot_isolation() {
+    let ring = HashRing::new();
+    let nodes = (0..10).map(|_| Node::random()).collect::<Vec<_>>();
+    nodes.iter().for_each(|node| ring.add(*node));
+
+    let snapshot = ring.snapshot();
+    assert_eq!(snapshot.epoch(), ring.epoch());
+    let keys = (0..1000).map(|_| random::<u64>()).collect::<Vec<_>>();
+    let primaries = keys
+        .iter()
+        .map(|key| *snapshot.node(key).expect("non-empty ring"))
+        .collect::<Vec<_>>();
+
+    // Modify the ring.
+    nodes.iter().take(5).for_each(|node| ring.remove(node));
+    ring.add(Node::random());
+    assert_eq!(ring.len(), 6);
+    assert!(snapshot.epoch() < ring.epoch());
+
+    // Snapshot is not affected.
+    assert_eq!(snapshot.len(), 10);
+    for (key, primary) in keys.iter().zip(primaries) {
+        assert_eq!(snapshot.node(key).as_deref(), Some(&primary));
+        assert_eq!(snapshot.replicas(key, 3)[0], primary);
+        assert!(snapshot.owns(&primary, key));
+        assert!(snapshot.intervals(&primary).is_some());
+    }
+    assert!(snapshot.diff(&snapshot.clone(), 3).is_empty());
+    assert!(!snapshot.diff(&ring.snapshot(), 3).is_empty());
+}
+
+#[test]
+fn concurrent_snapshots() {
+    let ring = HashRing::new();
+    let (tx, rx) = mpsc::channel();
+
+    let writer = {
+        let ring = ring.clone();
+        thread::spawn(move || {
+            for id in 0..2000 {
+                ring.add(Node { id });
+            }
+            tx.send(()).unwrap();
+        })
+    };
+
+    // Every snapshot observes the ring after some number of complete additions.
+    let mut last_epoch = 0;
+    loop {
+        let done = rx.try_recv().is_ok();
+        let snapshot = ring.snapshot();
+        assert_eq!(snapshot.len() as u64, snapshot.epoch());
+        assert!(snapshot.epoch() >= last_epoch);
+        last_epoch = snapshot.epoch();
+        if done {
+            break;
+        }
+    }
+    writer.join().unwrap();
+    assert_eq!(last_epoch, 2000);
+}
+
+#[test]
+fn snapshot_membership() {
+    let ring = HashRing::new();
+    let nodes = (0..10).map(|_| Node::random()).collect::<Vec<_>>();
+    nodes.iter().for_each(|node| ring.add(*node));
+
+    let snapshot = ring.snapshot();
+    ring.clear();
+    assert!(ring.is_empty());
+
+    assert_eq!(snapshot.nodes().count(), 10);
+    for node in &nodes {
+        assert!(snapshot.contains(node));
+        let token = snapshot.token_of(node).expect("node exists");
+        assert_eq!(snapshot.get(token.position()), Some(token));
+    }
+    let first = snapshot.nodes().next().expect("non-empty snapshot");
+    assert_eq!(
+        snapshot
+            .tokens(first.position(), mpchash::RingDirection::Clockwise)
+            .count(),
+        10
+    );
+}
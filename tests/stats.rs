@@ -0,0 +1,113 @@
+use {
+    mpchash::{HashRing, HashRingBuilder, LoadStats},
+    std::collections::HashMap,
+};
+
+fn ring(nodes: u64, probe_count: usize) -> HashRing<u64> {
+    let ring = HashRingBuilder::new().probe_count(probe_count).build();
+    (0..nodes).for_each(|i| ring.add(i));
+    ring
+}
+
+#[test]
+fn key_counts() {
+    let ring = ring(20, 23);
+    let stats = ring.stats_uniform(20_000);
+
+    assert_eq!(stats.total_keys, 20_000);
+    assert_eq!(stats.nodes.len(), 20);
+    assert_eq!(stats.nodes.iter().map(|s| s.keys).sum::<usize>(), 20_000);
+    assert!((stats.mean() - 1000.0).abs() < f64::EPSILON);
+    assert!(stats.std_dev() > 0.0);
+
+    // Nodes are ordered by position.
+    assert!(stats
+        .nodes
+        .windows(2)
+        .all(|w| w[0].positions[0] < w[1].positions[0]));
+
+    // Counts match the actual routing.
+    let mut owned = HashMap::new();
+    for key in 0..20_000u64 {
+        let node = *ring.node(&key).expect("non-empty ring").node();
+        *owned.entry(node).or_insert(0) += 1;
+    }
+    for node in &stats.nodes {
+        assert_eq!(node.keys, owned[&node.node]);
+    }
+
+    // Intervals cover the whole ring.
+    let keyspace = stats.nodes.iter().map(|s| s.keyspace).sum::<f64>();
+    assert!((keyspace - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn probe_count_improves_balance() {
+    let single = ring(50, 1).stats_uniform(50_000);
+    let multi = ring(50, 23).stats_uniform(50_000);
+    assert!(multi.peak_to_average() < single.peak_to_average());
+    assert!(multi.std_dev() < single.std_dev());
+    assert!(multi.peak_to_average() < 1.5);
+}
+
+#[test]
+fn weighted_ratio() {
+    let ring = HashRing::<u64>::new();
+    (0..10).for_each(|i| ring.add_weighted(i, 1 + (i % 2) as u32));
+    let stats = ring.stats_uniform(30_000);
+
+    // Heavier nodes get more keys, but the ratio is relative to the fair share.
+    let light = stats.nodes.iter().filter(|s| s.weight == 1).map(|s| s.keys);
+    let heavy = stats.nodes.iter().filter(|s| s.weight == 2).map(|s| s.keys);
+    assert!(heavy.sum::<usize>() > light.sum::<usize>());
+    assert!(stats.peak_to_average() < 1.5);
+}
+
+#[test]
+fn nodes_at_several_positions() {
+    let ring = HashRing::<u64>::new();
+    ring.insert(u64::MAX / 4, 1);
+    ring.insert(u64::MAX / 2, 2);
+    ring.insert(u64::MAX / 4 * 3, 1);
+    let stats = ring.stats_uniform(10_000);
+
+    // Tokens of the same node are aggregated.
+    assert_eq!(stats.nodes.len(), 2);
+    let node1 = &stats.nodes[0];
+    assert_eq!(node1.node, 1);
+    assert_eq!(node1.positions, vec![u64::MAX / 4, u64::MAX / 4 * 3]);
+    assert_eq!(node1.weight, 2);
+    assert!((node1.keyspace - 0.75).abs() < 1e-9);
+    assert!((stats.mean() - 5_000.0).abs() < f64::EPSILON);
+
+    let owned = (0..10_000u64)
+        .filter(|key| *ring.node(key).expect("non-empty ring").node() == 1)
+        .count();
+    assert_eq!(node1.keys, owned);
+    assert_eq!(stats.nodes[1].keys, 10_000 - owned);
+}
+
+#[test]
+fn empty() {
+    let ring = HashRing::<u64>::new();
+    let stats = ring.stats_uniform(100);
+    assert_eq!(stats.total_keys, 0);
+    assert!(stats.nodes.is_empty());
+    assert_eq!(stats.mean(), 0.0);
+    assert_eq!(stats.std_dev(), 0.0);
+    assert_eq!(stats.peak_to_average(), 0.0);
+
+    let stats = LoadStats::<u64> {
+        nodes: Vec::new(),
+        total_keys: 0,
+    };
+    assert_eq!(stats.peak_to_average(), 0.0);
+}
+
+#[test]
+fn custom_keys() {
+    let ring = ring(3, 23);
+    let stats = ring.stats(["a", "b", "c", "d"]);
+    assert_eq!(stats.total_keys, 4);
+    assert_eq!(stats.nodes.iter().map(|s| s.keys).sum::<usize>(), 4);
+}
@@ -0,0 +1,154 @@
+use {
+    mpchash::{HashRing, HashRingBuilder, RingDirection, RingEvent, SortedArrayStorage},
+    rand::random,
+    std::thread,
+};
+
+fn sorted_array_ring() -> HashRing<u64> {
+    HashRingBuilder::new().storage(SortedArrayStorage).build()
+}
+
+#[track_caller]
+fn assert_same(expected: &HashRing<u64>, actual: &HashRing<u64>) {
+    assert_eq!(expected.len(), actual.len());
+    let tokens = |ring: &HashRing<u64>, start, dir| {
+        ring.tokens(start, dir)
+            .map(|token| (token.position(), *token.node()))
+            .collect::<Vec<_>>()
+    };
+    for start in [0, random(), u64::MAX] {
+        for dir in [RingDirection::Clockwise, RingDirection::CounterClockwise] {
+            assert_eq!(tokens(expected, start, dir), tokens(actual, start, dir));
+        }
+        assert_eq!(expected.key_range(start), actual.key_range(start));
+    }
+    for key in 0..500u64 {
+        assert_eq!(
+            expected.node(&key).map(|token| token.position()),
+            actual.node(&key).map(|token| token.position())
+        );
+        assert_eq!(
+            expected
+                .replicas(&key, 3)
+                .iter()
+                .map(|token| token.position())
+                .collect::<Vec<_>>(),
+            actual
+                .replicas(&key, 3)
+                .iter()
+                .map(|token| token.position())
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn sorted_array_matches_skip_map() {
+    let expected = HashRing::new();
+    let actual = sorted_array_ring();
+    assert_same(&expected, &actual);
+
+    for _ in 0..50 {
+        let node = random::<u64>();
+        let weight = 1 + random::<u32>() % 3;
+        expected.add_weighted(node, weight);
+        actual.add_weighted(node, weight);
+    }
+    expected.insert(0, 1);
+    actual.insert(0, 1);
+    expected.insert(u64::MAX, 2);
+    actual.insert(u64::MAX, 2);
+    assert_same(&expected, &actual);
+
+    // Replace a node, remove some others.
+    expected.insert(0, 3);
+    actual.insert(0, 3);
+    for token in expected.nodes().step_by(3).collect::<Vec<_>>() {
+        expected.remove(token.node());
+        actual.remove(token.node());
+    }
+    assert_eq!(expected.remove_at(u64::MAX), actual.remove_at(u64::MAX));
+    assert_same(&expected, &actual);
+    assert_eq!(actual.get(0).as_deref(), Some(&3));
+    assert_eq!(expected.epoch(), actual.epoch());
+
+    expected.clear();
+    actual.clear();
+    assert!(actual.is_empty());
+    assert!(actual.node(&1).is_none());
+    assert_same(&expected, &actual);
+}
+
+#[test]
+fn sorted_array_tokens_outlive_removal() {
+    let ring = sorted_array_ring();
+    ring.insert(10, 1);
+    ring.insert(20, 2);
+
+    let token = ring.node(&"key").unwrap();
+    let node = *token.node();
+    ring.remove(&node);
+    ring.insert(token.position(), 3);
+    assert_eq!(token.node(), &node);
+
+    // Stale token doesn't remove the node which replaced it.
+    assert_eq!(ring.remove_at(token.position()).as_deref(), Some(&3));
+    assert_eq!(ring.len(), 1);
+}
+
+#[test]
+fn sorted_array_snapshot() {
+    let ring = sorted_array_ring();
+    (0..10).for_each(|node| ring.add(node));
+    let snapshot = ring.snapshot();
+    ring.remove(&3);
+    ring.add(10);
+
+    assert_eq!(snapshot.len(), 10);
+    assert!(snapshot.contains(&3));
+    assert!(!snapshot.contains(&10));
+    assert!(!ring.contains(&3));
+
+    // Restored ring uses the default storage.
+    let restored = HashRing::from_state(ring.to_state()).unwrap();
+    assert_same(&restored, &ring);
+}
+
+#[test]
+fn sorted_array_events() {
+    let ring = sorted_array_ring();
+    let events = ring.subscribe();
+
+    let pos = ring.try_add(1).unwrap();
+    ring.insert(pos, 2);
+    ring.remove_at(pos);
+    let events = events
+        .try_iter()
+        .map(|event| match event {
+            RingEvent::NodeAdded { node, .. } => (true, node),
+            RingEvent::NodeRemoved { node, .. } => (false, node),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(events, vec![(true, 1), (false, 1), (true, 2), (false, 2)]);
+}
+
+#[test]
+fn sorted_array_concurrent_writes() {
+    let ring = sorted_array_ring();
+    thread::scope(|scope| {
+        for thread in 0..4u64 {
+            let ring = &ring;
+            scope.spawn(move || {
+                for node in 0..100 {
+                    ring.add(thread * 1000 + node);
+                    assert!(ring.node(&node).is_some());
+                }
+            });
+        }
+    });
+
+    // No modification is lost.
+    assert_eq!(ring.len(), 400);
+    let tokens = ring.nodes().collect::<Vec<_>>();
+    assert!(tokens.windows(2).all(|w| w[0] < w[1]));
+}
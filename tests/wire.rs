@@ -0,0 +1,184 @@
+use {
+    mpchash::{
+        HashRing,
+        HashRingBuilder,
+        NodeCodec,
+        RawCodec,
+        StateError,
+        WireError,
+        Xxh3Partitioner,
+    },
+    std::{fs, path::Path},
+};
+
+/// Compares encoded ring with the golden file.
+///
+/// Set `UPDATE_GOLDEN` environment variable to (re)generate golden files,
+/// which must only happen along with the wire format version bump.
+fn assert_golden(name: &str, bytes: &[u8]) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, bytes).expect("write golden file");
+    }
+    let golden = fs::read(&path).expect("read golden file");
+    assert_eq!(bytes, golden, "encoding differs from {}", path.display());
+}
+
+fn golden_ring() -> HashRing<u64, Xxh3Partitioner> {
+    let ring = HashRingBuilder::new().seeds(7, 11).probe_count(21).build();
+    (1..=5).for_each(|i| ring.add(i));
+    ring.add_weighted(6, 3);
+    ring.insert(42, 100);
+    ring.remove(&2);
+    ring
+}
+
+/// Node with a name and a zone, encoded as a length-prefixed pair.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Node {
+    name: String,
+    zone: u8,
+}
+
+struct NodeCodecImpl;
+
+impl NodeCodec<Node> for NodeCodecImpl {
+    fn encode(&self, node: &Node, buf: &mut Vec<u8>) {
+        buf.push(node.zone);
+        buf.extend_from_slice(node.name.as_bytes());
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Node> {
+        let (&zone, name) = bytes.split_first()?;
+        let name = String::from_utf8(name.to_vec()).ok()?;
+        Some(Node { name, zone })
+    }
+}
+
+#[test]
+fn golden_u64() {
+    let ring = golden_ring();
+    let bytes = ring.encode(&RawCodec);
+    assert_golden("ring_u64_v1.bin", &bytes);
+
+    let restored = HashRing::<u64>::decode(&bytes, &RawCodec).expect("valid ring");
+    assert_eq!(restored.to_state().tokens, ring.to_state().tokens);
+    assert_eq!(restored.epoch(), ring.epoch());
+    assert_eq!(restored.probe_count(), 21);
+    assert_eq!(restored.weight(&6), Some(3));
+    for key in 0..1000u64 {
+        assert_eq!(
+            restored.node(&key).map(|token| *token.node()),
+            ring.node(&key).map(|token| *token.node())
+        );
+    }
+}
+
+#[test]
+fn golden_custom_codec() {
+    let ring = HashRing::new();
+    for (name, zone) in [("alpha", 1), ("beta", 2), ("gamma", 1)] {
+        ring.add(Node {
+            name: name.to_string(),
+            zone,
+        });
+    }
+    let bytes = ring.encode(&NodeCodecImpl);
+    assert_golden("ring_node_v1.bin", &bytes);
+
+    let restored = HashRing::<Node>::decode(&bytes, &NodeCodecImpl).expect("valid ring");
+    assert_eq!(restored.to_state().tokens, ring.to_state().tokens);
+}
+
+#[test]
+fn empty_ring() {
+    let ring = HashRing::<u64>::new();
+    let bytes = ring.encode(&RawCodec);
+    let restored = HashRing::<u64>::decode(&bytes, &RawCodec).expect("valid ring");
+    assert!(restored.is_empty());
+}
+
+#[test]
+fn compact() {
+    // Smaller than fixed-width position, node and weight per token.
+    let ring = HashRing::<u64>::new();
+    (0..1000).for_each(|i| ring.add(i));
+    let bytes = ring.encode(&RawCodec);
+    assert!(bytes.len() < 1000 * (8 + 8 + 4));
+}
+
+#[test]
+fn corrupted() {
+    let bytes = golden_ring().encode(&RawCodec);
+    let decode = |bytes: &[u8]| HashRing::<u64>::decode(bytes, &RawCodec).err();
+
+    assert_eq!(decode(&[]), Some(WireError::UnexpectedEof));
+    assert_eq!(decode(&bytes[..20]), Some(WireError::ChecksumMismatch));
+
+    let mut invalid = bytes.clone();
+    invalid[0] = b'X';
+    assert_eq!(decode(&invalid), Some(WireError::InvalidMagic));
+
+    // Any flipped bit is caught by the checksum.
+    for idx in 4..bytes.len() {
+        let mut invalid = bytes.clone();
+        invalid[idx] ^= 0x10;
+        assert_eq!(decode(&invalid), Some(WireError::ChecksumMismatch));
+    }
+
+    // Invalid node payload.
+    let err = HashRing::<u32>::decode(&bytes, &RawCodec).err();
+    assert_eq!(err, Some(WireError::InvalidNode));
+}
+
+#[test]
+fn invalid_contents() {
+    // Re-seal tampered body with a valid checksum.
+    let reseal = |mut body: Vec<u8>| {
+        let checksum = xxhash_rust::xxh3::xxh3_64(&body);
+        body.extend_from_slice(&checksum.to_le_bytes());
+        body
+    };
+    let bytes = golden_ring().encode(&RawCodec);
+    let body = bytes[..bytes.len() - 8].to_vec();
+    let decode = |bytes: &[u8]| HashRing::<u64>::decode(bytes, &RawCodec).err();
+
+    let mut invalid = body.clone();
+    invalid[4] = 2;
+    assert_eq!(
+        decode(&reseal(invalid)),
+        Some(WireError::UnsupportedVersion(2))
+    );
+
+    let mut invalid = body.clone();
+    invalid[5] = 1;
+    assert_eq!(decode(&reseal(invalid)), Some(WireError::UnsupportedFlags(1)));
+
+    let mut invalid = body.clone();
+    invalid[7] = 9;
+    assert_eq!(
+        decode(&reseal(invalid)),
+        Some(WireError::PartitionerMismatch(9))
+    );
+
+    let mut invalid = body.clone();
+    invalid.push(0);
+    assert_eq!(decode(&reseal(invalid)), Some(WireError::TrailingBytes));
+
+    let mut invalid = body.clone();
+    invalid[6] = 0;
+    assert_eq!(
+        decode(&reseal(invalid)),
+        Some(WireError::State(StateError::ZeroProbeCount))
+    );
+
+    // Different seeds change positions of the nodes.
+    let mut invalid = body.clone();
+    invalid[9] ^= 1;
+    assert!(matches!(
+        decode(&reseal(invalid)),
+        Some(WireError::State(StateError::PositionMismatch(_)))
+    ));
+}
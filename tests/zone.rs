@@ -0,0 +1,113 @@
+use {
+    mpchash::{HashRing, ZonedNode},
+    std::collections::{HashMap, HashSet},
+};
+
+#[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
+struct Node {
+    id: u64,
+    zone: u8,
+}
+
+impl ZonedNode for Node {
+    type Zone = u8;
+
+    fn zone(&self) -> Self::Zone {
+        self.zone
+    }
+}
+
+fn ring(nodes: u64, zones: u8) -> HashRing<Node> {
+    let ring = HashRing::new();
+    for id in 0..nodes {
+        ring.add(Node {
+            id,
+            zone: (id % u64::from(zones)) as u8,
+        });
+    }
+    ring
+}
+
+#[test]
+fn distinct_zones() {
+    let ring = ring(30, 3);
+    for key in 0..1000u64 {
+        let replicas = ring.replicas_diverse(&key, 3);
+        assert_eq!(replicas.len(), 3);
+
+        // Primary node is the same as for the plain replicas.
+        assert_eq!(replicas[0], ring.replicas(&key, 1)[0]);
+        assert_eq!(
+            replicas[0].node(),
+            ring.node(&key).expect("non-empty ring").node()
+        );
+
+        let zones = replicas.iter().map(|t| t.zone).collect::<HashSet<_>>();
+        assert_eq!(zones.len(), 3);
+    }
+}
+
+#[test]
+fn clockwise_order() {
+    let ring = ring(30, 3);
+    for key in 0..100u64 {
+        // Replicas are the first nodes from each zone, when moving clockwise.
+        let all = ring.replicas(&key, 30);
+        let mut expected = Vec::new();
+        for token in &all {
+            if expected.iter().all(|t: &Node| t.zone != token.zone) {
+                expected.push(*token.node());
+            }
+        }
+        let replicas = ring.replicas_diverse(&key, 3);
+        assert_eq!(
+            replicas.iter().map(|t| *t.node()).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn zones_exhausted() {
+    // Two zones, three replicas: the third replica comes from a used zone.
+    let two_zones = ring(10, 2);
+    for key in 0..100u64 {
+        let replicas = two_zones.replicas_diverse(&key, 3);
+        assert_eq!(replicas.len(), 3);
+        assert_ne!(replicas[0].zone, replicas[1].zone);
+        let ids = replicas.iter().map(|t| t.id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), 3);
+    }
+
+    // More replicas than nodes.
+    let small = ring(4, 2);
+    assert_eq!(small.replicas_diverse(&"key", 10).len(), 4);
+    assert!(small.replicas_diverse(&"key", 0).is_empty());
+
+    // Empty ring.
+    let empty = HashRing::<Node>::new();
+    assert!(empty.replicas_diverse(&"key", 3).is_empty());
+}
+
+#[test]
+fn side_table() {
+    let ring = HashRing::<u64>::new();
+    (0..12).for_each(|id| ring.add(id));
+    let zones = (0..12)
+        .map(|id| (id, ["a", "b", "c", "d"][id as usize % 4]))
+        .collect::<HashMap<_, _>>();
+
+    let snapshot = ring.snapshot();
+    for key in 0..100u64 {
+        let replicas = ring.replicas_diverse_by(&key, 4, |node| zones[node]);
+        let used = replicas
+            .iter()
+            .map(|t| zones[t.node()])
+            .collect::<HashSet<_>>();
+        assert_eq!(used.len(), 4);
+        assert_eq!(
+            snapshot.replicas_diverse_by(&key, 4, |node| zones[node]),
+            replicas
+        );
+    }
+}